@@ -0,0 +1,40 @@
+use std::{
+    io::BufRead,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+/// A command typed into the running skin changer.
+#[derive(Debug)]
+pub enum ConsoleCommand {
+    /// `profile <name>`
+    Profile(String),
+    /// `profiles`
+    Profiles,
+}
+
+/// Reads console commands from stdin on a background thread.
+pub fn spawn() -> Receiver<ConsoleCommand> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            let mut words = line.split_whitespace();
+            let command = match (words.next(), words.next()) {
+                (Some("profile"), Some(name)) => ConsoleCommand::Profile(name.to_string()),
+                (Some("profiles"), None) => ConsoleCommand::Profiles,
+                (None, _) => continue,
+                _ => {
+                    log::warn!("unknown console command {line:?}, try: profile <name>, profiles");
+                    continue;
+                }
+            };
+            if sender.send(command).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,33 @@
+/// Lists every ConVar, prints one by exact name, or lists the ones whose
+/// name contains the argument.
+pub fn dump(args: &[String]) -> bool {
+    let Some(cs2) = super::attach() else {
+        return false;
+    };
+
+    let mut convars = match args.first() {
+        Some(name) => match cs2.convar(name) {
+            Some(convar) => vec![convar],
+            None => {
+                let mut convars = cs2.convars();
+                convars.retain(|convar| convar.name.contains(name.as_str()));
+                convars
+            }
+        },
+        None => cs2.convars(),
+    };
+    convars.sort_by(|a, b| a.name.cmp(&b.name));
+
+    println!("{:<40} {:<8} {:<18} value", "name", "type", "flags");
+    for convar in &convars {
+        println!(
+            "{:<40} {:<8} 0x{:<16X} {}",
+            convar.name, convar.ty, convar.flags, convar.value
+        );
+        if !args.is_empty() && !convar.description.is_empty() {
+            println!("    {}", convar.description);
+        }
+    }
+    println!("{} convars", convars.len());
+    !convars.is_empty()
+}
@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use crate::{config::BASE_PATH, cs2::export::OffsetExport};
+
+pub fn export_offsets(args: &[String]) -> bool {
+    let dir = match args.first() {
+        Some(dir) => PathBuf::from(dir),
+        None => BASE_PATH.join("exports"),
+    };
+    let Some(cs2) = super::attach() else {
+        return false;
+    };
+
+    let build_id = cs2
+        .process
+        .module_build_id(cs2.offsets.library.client)
+        .unwrap_or_else(|| {
+            log::warn!("could not read libclient.so build id");
+            "unknown".to_string()
+        });
+
+    let export = OffsetExport::new(&cs2.offsets, build_id);
+    match export.write(&dir) {
+        Ok(dir) => {
+            log::info!("wrote offsets to {dir:?}");
+            true
+        }
+        Err(e) => {
+            log::error!("could not write offsets to {dir:?}: {e}");
+            false
+        }
+    }
+}
@@ -0,0 +1,10 @@
+pub fn dump() -> bool {
+    let Some(cs2) = super::attach() else {
+        return false;
+    };
+    println!("game state: {}", cs2.game_state());
+    if let Some(global_vars) = cs2.global_vars() {
+        println!("map:        {}", global_vars.map_name);
+    }
+    true
+}
@@ -0,0 +1,14 @@
+pub fn dump() -> bool {
+    let Some(cs2) = super::attach() else {
+        return false;
+    };
+    let Some(global_vars) = cs2.global_vars() else {
+        log::error!("global vars not available");
+        return false;
+    };
+    println!("tick count:   {}", global_vars.tick_count);
+    println!("frame count:  {}", global_vars.frame_count);
+    println!("current time: {:.3}", global_vars.current_time);
+    println!("map name:     {}", global_vars.map_name);
+    true
+}
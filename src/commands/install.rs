@@ -0,0 +1,30 @@
+use crate::game_data::{self, PAK_NAME};
+
+/// Prints where the game was found and which directory game data is read from.
+pub fn dump() -> bool {
+    match game_data::install() {
+        Some(install) => {
+            println!("found:       {:?}", install.content_dir);
+            println!("through:     {}", install.source);
+            match install.build_id {
+                Some(build_id) => println!("build id:    {build_id}"),
+                None => println!("build id:    unknown"),
+            }
+        }
+        None => println!("found:       nothing"),
+    }
+    let game_data = game_data::current();
+    if let Some(dir) = game_data.configured_content_dir() {
+        println!("game_dir:    {dir:?}");
+    }
+
+    let Some(dir) = game_data.content_dir() else {
+        return false;
+    };
+    let pak = dir.join(PAK_NAME);
+    println!(
+        "game data:   {pak:?}{}",
+        if pak.is_file() { "" } else { " (missing)" }
+    );
+    pak.is_file()
+}
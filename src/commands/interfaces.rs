@@ -0,0 +1,38 @@
+use crate::{constants::cs2, cs2::interfaces::{Interface, InterfaceRegistry}, os::process::Process};
+
+/// Lists every interface, or looks one up: `VEngineCvar007` matches that
+/// exact version, `VEngineCvar` the latest registered version.
+pub fn dump(args: &[String]) -> bool {
+    let Some(process) = Process::open(cs2::PROCESS_NAME) else {
+        log::error!("CS2 is not running");
+        return false;
+    };
+
+    let registry = InterfaceRegistry::new(&process);
+    let interfaces: Vec<&Interface> = match args.first() {
+        Some(name) if name.ends_with(|c: char| c.is_ascii_digit()) => cs2::LIBS
+            .iter()
+            .filter_map(|module| registry.exact(module, name))
+            .collect(),
+        Some(name) => cs2::LIBS
+            .iter()
+            .filter_map(|module| registry.latest(module, name))
+            .collect(),
+        None => registry.all().iter().collect(),
+    };
+
+    println!("{:<22} {:<40} {:<8} {:<18} offset", "module", "name", "version", "instance");
+    for interface in &interfaces {
+        let offset = process
+            .module_base_address(interface.module)
+            .and_then(|base| interface.instance.checked_sub(base))
+            .map(|offset| format!("0x{offset:X}"))
+            .unwrap_or_default();
+        println!(
+            "{:<22} {:<40} {:<8} 0x{:<16X} {}",
+            interface.module, interface.name, interface.version, interface.instance, offset
+        );
+    }
+    println!("{} interfaces", interfaces.len());
+    !interfaces.is_empty()
+}
@@ -0,0 +1,92 @@
+use crate::game_data;
+
+/// Looks up item definitions, paint kits and sticker kits by id, or by a
+/// part of their internal or localized name.
+pub fn dump(args: &[String]) -> bool {
+    let game_data = game_data::current();
+    let Some(items) = game_data.items() else {
+        log::error!(
+            "no item database, set game_dir in the config or copy items_game.txt into the config directory"
+        );
+        return false;
+    };
+    let Some(query) = args.first().map(|query| query.to_ascii_lowercase()) else {
+        println!(
+            "{} items, {} paint kits, {} sticker kits",
+            items.items().count(),
+            items.paint_kits().count(),
+            items.sticker_kits().count()
+        );
+        if let Some(localization) = game_data.localization() {
+            println!("{} {} tokens", localization.len(), localization.language());
+        }
+        return true;
+    };
+    let id = query.parse::<u32>().ok();
+    let matches = |index: u32, name: &str, localized: Option<&str>| match id {
+        Some(id) => id == index,
+        None => {
+            name.contains(&query)
+                || localized.is_some_and(|localized| localized.to_lowercase().contains(&query))
+        }
+    };
+
+    let mut found = 0;
+    for item in items.items() {
+        let localized = item.item_name.as_deref().and_then(|token| game_data.localize(token));
+        if !matches(item.index, &item.name, localized) {
+            continue;
+        }
+        let paint_kits = items
+            .weapon_paint_kits(&item.name)
+            .map_or(0, |kits| kits.len());
+        println!(
+            "item {:<6} {:<32} {:<36} {} paint kits, prefabs: {}",
+            item.index,
+            item.name,
+            localized.or(item.item_name.as_deref()).unwrap_or("-"),
+            paint_kits,
+            item.prefabs.join(" ")
+        );
+        found += 1;
+    }
+    for kit in items.paint_kits() {
+        let localized = game_data.paint_kit_name(kit.id);
+        if !matches(kit.id, &kit.name, localized) {
+            continue;
+        }
+        let rarity = kit
+            .rarity
+            .as_deref()
+            .map(|name| match items.rarity(name) {
+                Some(rarity) => format!("{} ({})", rarity.name, rarity.value),
+                None => name.to_string(),
+            })
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "paint kit {:<6} {:<32} {:<36} float {:.2}-{:.2}, rarity {}",
+            kit.id,
+            kit.name,
+            localized.or(kit.description_tag.as_deref()).unwrap_or("-"),
+            kit.min_float,
+            kit.max_float,
+            rarity
+        );
+        found += 1;
+    }
+    for kit in items.sticker_kits() {
+        let localized = game_data.sticker_kit_name(kit.id);
+        if !matches(kit.id, &kit.name, localized) {
+            continue;
+        }
+        println!(
+            "sticker kit {:<6} {:<32} {}",
+            kit.id,
+            kit.name,
+            localized.or(kit.item_name.as_deref()).unwrap_or("-")
+        );
+        found += 1;
+    }
+    println!("{found} matches");
+    found > 0
+}
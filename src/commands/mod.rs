@@ -0,0 +1,105 @@
+pub mod console;
+mod convars;
+mod export;
+mod game_state;
+mod global_vars;
+mod install;
+mod interfaces;
+mod items;
+pub mod profiles;
+mod schema;
+mod self_test;
+mod validate;
+mod vpk;
+
+use crate::{
+    config::{CONFIG_PATH, DEFAULT_CONFIG_NAME, parse_config},
+    cs2::CS2,
+    game_data,
+};
+
+const USAGE: &str = "\
+usage: cs2-skin-changer [--profile <name>] [command]
+
+Without a command the skin changer runs with profile <name>, or the config's
+active_profile. While it runs, `profile <name>` on stdin switches profiles and
+`profiles` lists them.
+
+commands:
+  schema-dump [file]          write the live schema to [file] (default: schema.json in the config dir)
+  schema-diff <old> [new]     compare two schema dumps, or <old> against the live game
+  schema-offsets <snapshot>   resolve schema offsets from a snapshot or cs2-dumper client_dll.json
+  interfaces [name]           list every registered interface, or look up <name>
+                              exactly (VEngineCvar007) or by latest version (VEngineCvar)
+  global-vars                 print the client's tick count, frame count, time and map
+  game-state                  print the detected game state (menu, loading, warmup, live, ...)
+  convars [name]              list the client's ConVars with type, flags and value,
+                              or the one called <name> (or containing it)
+  install                     print where CS2 is installed, its build id and the VPK game data is read from
+  items [query]               look up item definitions, paint kits and sticker kits in
+                              items_game.txt by id or by part of their name
+  profiles                    list the config's profiles
+  validate-config [file]      check the config file (default: the one in the config dir)
+  vpk [archive] list [filter] list the files in the game's pak01_dir.vpk, or in [archive]
+  vpk [archive] extract <path> [out]
+                              extract a file after checking its CRC (default: its file name)
+  self-test                   check that the found offsets point where they should
+  export-offsets [dir]        write the found offsets as cs2-dumper JSON, a Rust module and a C header";
+
+/// Runs a one-shot command instead of the skin changer loop.
+/// Returns whether the command succeeded.
+pub fn run(command: &str, args: &[String]) -> bool {
+    match command {
+        "schema-dump" => schema::dump(args),
+        "schema-diff" => schema::diff(args),
+        "schema-offsets" => schema::offsets(args),
+        "export-offsets" => export::export_offsets(args),
+        "interfaces" => interfaces::dump(args),
+        "self-test" => self_test::self_test(),
+        "global-vars" => global_vars::dump(),
+        "game-state" => game_state::dump(),
+        "convars" => convars::dump(args),
+        "install" => {
+            configure_game_data();
+            install::dump()
+        }
+        "items" => {
+            configure_game_data();
+            items::dump(args)
+        }
+        "profiles" => profiles::dump(),
+        "validate-config" => validate::validate(args),
+        "vpk" => {
+            configure_game_data();
+            vpk::vpk(args)
+        }
+        "help" | "-h" | "--help" => {
+            println!("{USAGE}");
+            true
+        }
+        _ => {
+            log::error!("unknown command {command}");
+            println!("{USAGE}");
+            false
+        }
+    }
+}
+
+/// Reads game data from the config's `game_dir` in its `language`.
+fn configure_game_data() {
+    if let Some(config) = parse_config(&CONFIG_PATH.join(DEFAULT_CONFIG_NAME)) {
+        game_data::configure(config.game_dir, &config.language);
+    }
+}
+
+/// Attaches to the running game and finds offsets, like the main loop does.
+fn attach() -> Option<CS2> {
+    let config = parse_config(&CONFIG_PATH.join(DEFAULT_CONFIG_NAME))?;
+    let mut cs2 = CS2::new();
+    cs2.setup(&config);
+    if !cs2.is_valid() {
+        log::error!("could not attach to CS2");
+        return None;
+    }
+    Some(cs2)
+}
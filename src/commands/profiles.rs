@@ -0,0 +1,21 @@
+use crate::config::{BASE_PROFILE, CONFIG_PATH, DEFAULT_CONFIG_NAME, SkinChangerConfig, parse_config};
+
+pub fn dump() -> bool {
+    let Some(config) = parse_config(&CONFIG_PATH.join(DEFAULT_CONFIG_NAME)) else {
+        return false;
+    };
+    print(&config, config.active_profile.as_deref());
+    true
+}
+
+/// Lists the base config and every profile, marking `active`.
+pub fn print(config: &SkinChangerConfig, active: Option<&str>) {
+    let marker = |name| if active.unwrap_or(BASE_PROFILE) == name { "*" } else { " " };
+    println!("{} {BASE_PROFILE}", marker(BASE_PROFILE));
+    for (name, profile) in &config.profiles {
+        match &profile.inherits {
+            Some(parent) => println!("{} {name} (inherits {parent})", marker(name)),
+            None => println!("{} {name}", marker(name)),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::BASE_PATH,
+    constants::cs2,
+    cs2::{
+        find_offsets::{SCHEMA_FIELDS, resolve_schema_offsets},
+        offsets::Offsets,
+        schema::{SCHEMA_SNAPSHOT_NAME, Schema},
+        schema_diff,
+    },
+    os::process::Process,
+};
+
+fn live_schema() -> Option<Schema> {
+    let Some(process) = Process::open(cs2::PROCESS_NAME) else {
+        log::error!("CS2 is not running");
+        return None;
+    };
+    let schema_module = process.module_base_address(cs2::SCHEMA_LIB)?;
+    let schema = Schema::new(&process, schema_module);
+    if schema.is_none() {
+        log::error!("could not read schema system");
+    }
+    schema
+}
+
+fn load_schema(path: &str) -> Option<Schema> {
+    match Schema::load(Path::new(path)) {
+        Ok(schema) => Some(schema),
+        Err(e) => {
+            log::error!("could not load schema dump {path}: {e}");
+            None
+        }
+    }
+}
+
+pub fn dump(args: &[String]) -> bool {
+    let path = match args.first() {
+        Some(path) => PathBuf::from(path),
+        None => BASE_PATH.join(SCHEMA_SNAPSHOT_NAME),
+    };
+    let Some(schema) = live_schema() else {
+        return false;
+    };
+    if let Err(e) = schema.save(&path) {
+        log::error!("could not write schema dump {path:?}: {e}");
+        return false;
+    }
+    log::info!("wrote schema dump to {path:?}");
+    true
+}
+
+/// Resolves the schema part of the offsets from a snapshot, without a running game.
+pub fn offsets(args: &[String]) -> bool {
+    let Some(schema) = args.first().and_then(|path| load_schema(path)) else {
+        if args.is_empty() {
+            log::error!("usage: schema-offsets <snapshot>");
+        }
+        return false;
+    };
+
+    let mut offsets = Offsets::default();
+    let report = resolve_schema_offsets(&schema, &mut offsets);
+    for entry in SCHEMA_FIELDS {
+        let value = match entry.get(&offsets) {
+            Some(offset) if report.missing.iter().all(|m| m.name != entry.name) => {
+                format!("0x{offset:X}")
+            }
+            _ => "missing".to_string(),
+        };
+        println!("{:<36} {:<48} {value}", entry.name, format!("{}::{}", entry.class, entry.field));
+    }
+    println!(
+        "{:<36} {:<48} 0x{:X}",
+        "entity_identity.size", "CEntityIdentity", offsets.entity_identity.size
+    );
+
+    if !report.is_empty() {
+        println!("{report}");
+    }
+    !report.missing_required()
+}
+
+pub fn diff(args: &[String]) -> bool {
+    let Some(old) = args.first().and_then(|path| load_schema(path)) else {
+        if args.is_empty() {
+            log::error!("usage: schema-diff <old> [new]");
+        }
+        return false;
+    };
+    let new = match args.get(1) {
+        Some(path) => load_schema(path),
+        None => live_schema(),
+    };
+    let Some(new) = new else {
+        return false;
+    };
+
+    let changes = schema_diff::diff(&old, &new);
+    for change in &changes {
+        println!("{change}");
+    }
+
+    let referenced = changes.iter().filter(|c| c.referenced().is_some()).count();
+    let breaking = changes.iter().filter(|c| c.is_breaking()).count();
+    println!(
+        "{} changes, {} affecting offsets (*), {} breaking (!!)",
+        changes.len(),
+        referenced,
+        breaking
+    );
+
+    if breaking > 0 {
+        log::error!("required schema fields moved or disappeared");
+        return false;
+    }
+    true
+}
@@ -0,0 +1,13 @@
+pub fn self_test() -> bool {
+    let Some(cs2) = super::attach() else {
+        return false;
+    };
+
+    let checks = cs2.self_test();
+    for check in &checks {
+        println!("{check}");
+    }
+    let failed = checks.iter().filter(|c| c.failed()).count();
+    println!("{} checks, {} failed", checks.len(), failed);
+    failed == 0
+}
@@ -0,0 +1,20 @@
+use std::path::PathBuf;
+
+use crate::config::{CONFIG_PATH, DEFAULT_CONFIG_NAME, validate_config};
+
+/// Prints every problem in the config file, fails if any is an error.
+pub fn validate(args: &[String]) -> bool {
+    let path = args
+        .first()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| CONFIG_PATH.join(DEFAULT_CONFIG_NAME));
+
+    let (_, diagnostics) = validate_config(&path);
+    for diagnostic in &diagnostics {
+        println!("{diagnostic}");
+    }
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.len() - errors;
+    println!("{errors} errors, {warnings} warnings");
+    errors == 0
+}
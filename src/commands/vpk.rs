@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+use crate::game_data::{self, PAK_NAME, vpk::Vpk};
+
+/// `vpk [archive] list [filter]` lists files, `vpk [archive] extract <path> [out]`
+/// writes one out after checking its CRC. The archive defaults to the
+/// game's `pak01_dir.vpk`.
+pub fn vpk(args: &[String]) -> bool {
+    let (opened, args) = match args.first() {
+        Some(path) if path.ends_with(".vpk") => match Vpk::open(Path::new(path)) {
+            Ok(vpk) => (Some(vpk), &args[1..]),
+            Err(e) => {
+                log::error!("could not open {path}: {e}");
+                return false;
+            }
+        },
+        _ => (None, args),
+    };
+    let game_data = game_data::current();
+    let vpk = match &opened {
+        Some(vpk) => vpk,
+        None => match game_data.pak() {
+            Some(vpk) => vpk,
+            None => {
+                log::error!("no archive given and no {PAK_NAME} found, see the install command");
+                return false;
+            }
+        },
+    };
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("list"), filter) => {
+            let filter = filter.map(|filter| filter.to_ascii_lowercase());
+            let mut count = 0;
+            for entry in vpk.entries().filter(|entry| {
+                filter
+                    .as_ref()
+                    .is_none_or(|f| entry.path.contains(f.as_str()))
+            }) {
+                println!(
+                    "{:>10} {:>5} 0x{:08X} {}",
+                    entry.size(),
+                    entry.archive_index,
+                    entry.crc,
+                    entry.path
+                );
+                count += 1;
+            }
+            println!("{count} files");
+            true
+        }
+        (Some("extract"), Some(path)) => {
+            let out = args
+                .get(2)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(path.rsplit(['/', '\\']).next().unwrap_or(path)));
+            let data = match vpk.read(path) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::error!("{e}");
+                    return false;
+                }
+            };
+            match std::fs::write(&out, &data) {
+                Ok(()) => {
+                    log::info!("wrote {} bytes to {out:?}", data.len());
+                    true
+                }
+                Err(e) => {
+                    log::error!("could not write {out:?}: {e}");
+                    false
+                }
+            }
+        }
+        _ => {
+            log::error!("usage: vpk [archive] list [filter] | vpk [archive] extract <path> [out]");
+            false
+        }
+    }
+}
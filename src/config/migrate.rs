@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use toml::{Table, Value};
+
+/// Version written into new config files.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// `MIGRATIONS[i]` upgrades a version `i + 1` table to version `i + 2`.
+/// Files without a `version` key are version 1.
+const MIGRATIONS: &[fn(&mut Table)] = &[
+    // 1 -> 2: adds the version key, no key was renamed or removed
+    |_| {},
+];
+
+/// Version of a config table, `None` when it is not a positive integer.
+pub fn version(table: &Table) -> Option<u32> {
+    match table.get("version") {
+        None => Some(1),
+        Some(value) => value
+            .as_integer()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v > 0),
+    }
+}
+
+/// Upgrades `table` to [`CONFIG_VERSION`]. Returns the version it had, or
+/// `None` if it was already current.
+pub fn migrate(table: &mut Table) -> Result<Option<u32>, String> {
+    let Some(old) = version(table) else {
+        return Err("version is not a positive integer".to_string());
+    };
+    if old > CONFIG_VERSION {
+        return Err(format!(
+            "version {old} is newer than this build supports ({CONFIG_VERSION})"
+        ));
+    }
+    if old == CONFIG_VERSION {
+        return Ok(None);
+    }
+
+    for migration in &MIGRATIONS[(old - 1) as usize..] {
+        migration(table);
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION as i64));
+    Ok(Some(old))
+}
+
+/// `cs2-skin-changer.toml` -> `cs2-skin-changer.toml.v1.bak`
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}.bak"));
+    path.with_file_name(name)
+}
+
+/// Upgrades the config file at `path` in place when it is older than
+/// [`CONFIG_VERSION`], after copying the original next to it. Current files
+/// are not written. Files that do not parse are left for validation to
+/// report.
+pub fn migrate_file(path: &Path) -> Result<(), String> {
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Ok(());
+    };
+    let Ok(original) = source.parse::<Table>() else {
+        return Ok(());
+    };
+    let mut table = original.clone();
+    let Some(old) = migrate(&mut table)? else {
+        return Ok(());
+    };
+
+    let backup = backup_path(path, old);
+    std::fs::copy(path, &backup).map_err(|e| format!("could not back up config: {e}"))?;
+
+    // when only the version was added, prepend it and keep the user's comments
+    table.remove("version");
+    let out = if !original.contains_key("version") && table == original {
+        format!("version = {CONFIG_VERSION}\n\n{source}")
+    } else {
+        table.insert("version".to_string(), Value::Integer(CONFIG_VERSION as i64));
+        toml::to_string(&table).map_err(|e| format!("could not write config: {e}"))?
+    };
+    std::fs::write(path, out).map_err(|e| format!("could not write config: {e}"))?;
+    log::info!(
+        "migrated config from version {old} to {CONFIG_VERSION}, original saved as {backup:?}"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(source: &str) -> Table {
+        source.parse().unwrap()
+    }
+
+    #[test]
+    fn migrates_old_tables() {
+        let mut old = table("enabled = true");
+        assert_eq!(migrate(&mut old), Ok(Some(1)));
+        assert_eq!(version(&old), Some(CONFIG_VERSION));
+        assert_eq!(old["enabled"].as_bool(), Some(true));
+
+        let mut current = table(&format!("version = {CONFIG_VERSION}"));
+        assert_eq!(migrate(&mut current), Ok(None));
+    }
+
+    #[test]
+    fn rejects_newer_and_invalid_versions() {
+        let mut newer = table(&format!("version = {}", CONFIG_VERSION + 1));
+        assert!(migrate(&mut newer).unwrap_err().contains("newer"));
+        for invalid in ["version = 0", "version = -1", "version = \"2\""] {
+            assert_eq!(version(&table(invalid)), None, "{invalid}");
+            assert!(migrate(&mut table(invalid)).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn migrates_file_once() {
+        let path = std::env::temp_dir().join(format!("migrate-{}.toml", std::process::id()));
+        let source = "# my skins\nenabled = true\n";
+        std::fs::write(&path, source).unwrap();
+
+        migrate_file(&path).unwrap();
+        let backup = backup_path(&path, 1);
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), source);
+        let migrated = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(migrated, format!("version = {CONFIG_VERSION}\n\n{source}"));
+
+        // a current file is left alone
+        std::fs::remove_file(&backup).unwrap();
+        migrate_file(&path).unwrap();
+        assert!(!backup.exists());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), migrated);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
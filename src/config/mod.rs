@@ -0,0 +1,635 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+mod migrate;
+mod presets;
+mod validate;
+mod wear;
+
+pub use migrate::{CONFIG_VERSION, migrate_file};
+pub use presets::{PRESETS_NAME, Presets};
+pub use validate::{Diagnostic, validate_config};
+pub use wear::{Condition, Wear};
+
+use crate::{
+    cs2::{
+        entity::weapon::Weapon,
+        loadout::{GameMode, Team},
+    },
+    game_data::{self, GameData, lookup},
+};
+
+pub const DEFAULT_CONFIG_NAME: &str = "cs2-skin-changer.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkinChangerConfig {
+    /// Config format version, older files are migrated on start.
+    pub version: u32,
+    pub enabled: bool,
+    /// Profile used when `--profile` is not given, the base config if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    pub skins: HashMap<Weapon, WeaponSkinConfig>,
+    /// Named weapon sets that override `skins` when a rule selects them.
+    pub loadouts: HashMap<String, Loadout>,
+    /// Checked in order, the first matching rule selects the loadout.
+    pub rules: Vec<LoadoutRule>,
+    pub profiles: BTreeMap<String, Profile>,
+    /// `game/csgo` directory of the install, game data is read from its VPKs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_dir: Option<PathBuf>,
+    /// Language of weapon and skin names, `csgo_<language>.txt` in the game files.
+    pub language: String,
+    pub offset_sources: OffsetSourcesConfig,
+}
+
+impl Default for SkinChangerConfig {
+    fn default() -> Self {
+        let mut skins = HashMap::new();
+        // knife models are left to the `knife` entry
+        for weapon in Weapon::iter() {
+            if weapon == Weapon::Unknown || weapon.fallback().is_some() {
+                continue;
+            }
+            skins.insert(weapon, WeaponSkinConfig::default());
+        }
+        Self {
+            version: CONFIG_VERSION,
+            enabled: false,
+            active_profile: None,
+            skins,
+            loadouts: HashMap::new(),
+            rules: Vec::new(),
+            profiles: BTreeMap::new(),
+            game_dir: None,
+            language: game_data::DEFAULT_LANGUAGE.to_string(),
+            offset_sources: OffsetSourcesConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeaponSkinConfig {
+    pub enabled: bool,
+    pub paint_kit: PaintKitRef,
+    pub seed: i32,
+    /// Float, or a condition such as `"field_tested"`.
+    pub wear: Wear,
+    pub stattrak: i32,
+    pub name_tag: String,
+    /// Phase from the preset library, such as `"ruby"`; sets the paint kit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<String>,
+    /// Pattern from the preset library, such as `"blue_gem_661"`; sets the
+    /// paint kit and seed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+impl Default for WeaponSkinConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            paint_kit: PaintKitRef::Id(0),
+            seed: 0,
+            wear: Wear::Float(0.0),
+            stattrak: -1,
+            name_tag: String::new(),
+            phase: None,
+            pattern: None,
+        }
+    }
+}
+
+/// A paint kit ID, or a name (`"Asiimov"`, `"AK-47 | Fire Serpent"`) that
+/// is resolved through the item database when the config is loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PaintKitRef {
+    Id(i32),
+    Name(String),
+}
+
+impl PaintKitRef {
+    /// The ID, 0 for names that were not resolved.
+    pub fn id(&self) -> i32 {
+        match self {
+            PaintKitRef::Id(id) => *id,
+            PaintKitRef::Name(_) => 0,
+        }
+    }
+}
+
+impl Display for PaintKitRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaintKitRef::Id(id) => write!(f, "{id}"),
+            PaintKitRef::Name(name) => write!(f, "{name:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Loadout {
+    pub skins: HashMap<Weapon, WeaponSkinConfig>,
+}
+
+/// Selects `loadout` when every condition that is set holds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoadoutRule {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub team: Option<Team>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub map: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<GameMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warmup: Option<bool>,
+    pub loadout: String,
+}
+
+/// Overrides on top of the profile it inherits from, or the base config.
+/// Weapons and loadouts replace the inherited entry of the same name, rules
+/// replace the inherited list.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inherits: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    pub skins: HashMap<Weapon, WeaponSkinConfig>,
+    pub loadouts: HashMap<String, Loadout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<LoadoutRule>>,
+}
+
+/// Name of the base config, when no profile with that name exists.
+pub const BASE_PROFILE: &str = "default";
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Unknown(String),
+    Cycle(Vec<String>),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::Unknown(name) => write!(f, "unknown profile {name:?}"),
+            ProfileError::Cycle(chain) => {
+                write!(f, "profile inheritance cycle: {}", chain.join(" -> "))
+            }
+        }
+    }
+}
+
+impl SkinChangerConfig {
+    /// The effective config for profile `name`, or for `active_profile` when
+    /// `name` is `None`: the base config with every profile in the
+    /// inheritance chain applied on top, starting from the root.
+    pub fn resolve_profile(&self, name: Option<&str>) -> Result<SkinChangerConfig, ProfileError> {
+        let Some(name) = name.or(self.active_profile.as_deref()) else {
+            return Ok(self.clone());
+        };
+        if name == BASE_PROFILE && !self.profiles.contains_key(name) {
+            return Ok(SkinChangerConfig {
+                active_profile: None,
+                ..self.clone()
+            });
+        }
+
+        let mut chain: Vec<&str> = Vec::new();
+        let mut next = Some(name);
+        while let Some(name) = next {
+            if chain.contains(&name) {
+                chain.push(name);
+                return Err(ProfileError::Cycle(
+                    chain.into_iter().map(str::to_string).collect(),
+                ));
+            }
+            let profile = self
+                .profiles
+                .get(name)
+                .ok_or_else(|| ProfileError::Unknown(name.to_string()))?;
+            chain.push(name);
+            next = profile.inherits.as_deref();
+        }
+
+        let mut config = self.clone();
+        for profile in chain.iter().rev().map(|name| &self.profiles[*name]) {
+            if let Some(enabled) = profile.enabled {
+                config.enabled = enabled;
+            }
+            config.skins.extend(profile.skins.clone());
+            config.loadouts.extend(profile.loadouts.clone());
+            if let Some(rules) = &profile.rules {
+                config.rules = rules.clone();
+            }
+        }
+        config.active_profile = Some(name.to_string());
+        Ok(config)
+    }
+
+    /// Applies phases and patterns from `presets`, replaces paint kit names
+    /// with their IDs and wear conditions with floats in the paint kit's
+    /// range. Entries that do not resolve are left for validation to report.
+    pub fn resolve_skins(&mut self, presets: &Presets, game_data: &GameData) {
+        let skins = std::iter::once(&mut self.skins)
+            .chain(self.loadouts.values_mut().map(|l| &mut l.skins))
+            .chain(self.profiles.values_mut().flat_map(|p| {
+                std::iter::once(&mut p.skins).chain(p.loadouts.values_mut().map(|l| &mut l.skins))
+            }));
+        for skins in skins {
+            for (weapon, skin) in skins.iter_mut() {
+                if let Some(phase) = &skin.phase
+                    && let Ok(id) = presets.phase(weapon, &skin.paint_kit, phase)
+                {
+                    skin.paint_kit = PaintKitRef::Id(id);
+                }
+                if let Some(pattern) = &skin.pattern
+                    && let Ok(pattern) = presets.pattern(weapon, pattern)
+                {
+                    skin.paint_kit = PaintKitRef::Id(pattern.paint_kit);
+                    skin.seed = pattern.seed;
+                }
+                if let PaintKitRef::Name(name) = &skin.paint_kit
+                    && let Ok(id) = lookup::find_paint_kit(game_data, weapon, name)
+                {
+                    skin.paint_kit = PaintKitRef::Id(id as i32);
+                }
+                if let Ok(wear) = skin.wear.resolve(wear::float_range(game_data, skin.paint_kit.id())) {
+                    skin.wear = Wear::Float(wear);
+                }
+            }
+        }
+    }
+
+    /// Weapons whose skin differs from `other`, in the base skins or any
+    /// loadout. Changed rules can select any loadout, so they mark every
+    /// weapon a loadout lists.
+    pub fn changed_weapons(&self, other: &SkinChangerConfig) -> HashSet<Weapon> {
+        fn compare(
+            a: Option<&HashMap<Weapon, WeaponSkinConfig>>,
+            b: Option<&HashMap<Weapon, WeaponSkinConfig>>,
+            changed: &mut HashSet<Weapon>,
+        ) {
+            let weapons = a.into_iter().chain(b).flat_map(|skins| skins.keys());
+            for weapon in weapons {
+                if a.and_then(|a| a.get(weapon)) != b.and_then(|b| b.get(weapon)) {
+                    changed.insert(weapon.clone());
+                }
+            }
+        }
+
+        let mut changed = HashSet::new();
+        compare(Some(&self.skins), Some(&other.skins), &mut changed);
+        let names: HashSet<&String> = self.loadouts.keys().chain(other.loadouts.keys()).collect();
+        for name in names {
+            let a = self.loadouts.get(name).map(|l| &l.skins);
+            let b = other.loadouts.get(name).map(|l| &l.skins);
+            compare(a, b, &mut changed);
+        }
+        if self.rules != other.rules || self.enabled != other.enabled {
+            let loadouts = self.loadouts.values().chain(other.loadouts.values());
+            changed.extend(loadouts.flat_map(|l| l.skins.keys().cloned()));
+        }
+        changed
+    }
+}
+
+/// Where cs2-dumper offsets and schema files are loaded from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OffsetSourcesConfig {
+    /// Only use local files and previously cached downloads.
+    pub offline: bool,
+    pub timeout_secs: u64,
+    /// Cached downloads younger than this are used without asking the server.
+    pub max_age_secs: u64,
+    /// Tried in order, the first source that loads and parses wins.
+    pub sources: Vec<OffsetSource>,
+}
+
+impl Default for OffsetSourcesConfig {
+    fn default() -> Self {
+        let file = |kind, path: &str| OffsetSource {
+            kind,
+            path: Some(PathBuf::from(path)),
+            url: None,
+            build: None,
+            crc32: None,
+        };
+        let url = |kind, url: &str| OffsetSource {
+            kind,
+            path: None,
+            url: Some(url.to_string()),
+            build: None,
+            crc32: None,
+        };
+        Self {
+            offline: false,
+            timeout_secs: 10,
+            max_age_secs: 60 * 60,
+            sources: vec![
+                file(SourceKind::Offsets, "offsets.json"),
+                file(SourceKind::Schema, "client_dll.json"),
+                url(
+                    SourceKind::Offsets,
+                    "https://raw.githubusercontent.com/sezzyaep/CS2-OFFSETS/main/offsets.json",
+                ),
+                url(
+                    SourceKind::Offsets,
+                    "https://raw.githubusercontent.com/hxuanyu/cs2-dumper/main/output/linux/offsets.json",
+                ),
+                url(
+                    SourceKind::Offsets,
+                    "https://raw.githubusercontent.com/a2x/cs2-dumper/main/output/linux/offsets.json",
+                ),
+                url(
+                    SourceKind::Schema,
+                    "https://raw.githubusercontent.com/hxuanyu/cs2-dumper/main/output/linux/client_dll.json",
+                ),
+                url(
+                    SourceKind::Schema,
+                    "https://raw.githubusercontent.com/a2x/cs2-dumper/main/output/linux/client_dll.json",
+                ),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// cs2-dumper `offsets.json`
+    Offsets,
+    /// cs2-dumper `client_dll.json`, or a schema snapshot
+    Schema,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffsetSource {
+    pub kind: SourceKind,
+    /// Local file, relative paths are resolved against the config directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Steam build ID (`buildid` in `appmanifest_730.acf`) of the game this
+    /// source was dumped from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<u32>,
+    /// CRC-32 the content must have, for sources pinned to one file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crc32: Option<u32>,
+}
+
+pub static BASE_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
+    let path = std::env::var_os("XDG_CONFIG_HOME")
+        .and_then(|p| {
+            if p.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(p))
+            }
+        })
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|base| base.join("cs2-skin-changer"))
+        .unwrap_or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(|p| p.to_path_buf()))
+                .unwrap_or_else(|| PathBuf::from("."))
+        });
+    if !path.exists() {
+        let _ = std::fs::create_dir_all(&path);
+    }
+    path
+});
+
+pub static CONFIG_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
+    let path = BASE_PATH.clone();
+    if !path.exists() {
+        let _ = std::fs::create_dir_all(&path);
+    }
+    path
+});
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file has errors, each one is logged by [`load_config`].
+    Invalid(Vec<Diagnostic>),
+    Profile(ProfileError),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Invalid(diagnostics) => {
+                let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+                write!(f, "config has {errors} errors")
+            }
+            ConfigError::Profile(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Validates the config file, logs every diagnostic, and
+/// resolves `profile` (or its `active_profile`). Returns the file as written
+/// and the effective config. Never falls back to defaults.
+pub fn load_config(
+    path: &Path,
+    profile: Option<&str>,
+) -> Result<(SkinChangerConfig, SkinChangerConfig), ConfigError> {
+    let (config, diagnostics) = validate_config(path);
+    for diagnostic in &diagnostics {
+        diagnostic.log();
+    }
+    let Some(config) = config else {
+        return Err(ConfigError::Invalid(diagnostics));
+    };
+    let effective = config.resolve_profile(profile).map_err(ConfigError::Profile)?;
+    Ok((config, effective))
+}
+
+/// The config file as written, defaults if it does not exist, `None` if it
+/// has errors.
+pub fn parse_config(path: &Path) -> Option<SkinChangerConfig> {
+    if !path.exists() {
+        return Some(SkinChangerConfig::default());
+    }
+    let (config, _) = load_config(path, None).ok()?;
+    log::info!("loaded config {path:?}");
+    Some(config)
+}
+
+pub fn write_config(config: &SkinChangerConfig, path: &Path) -> io::Result<()> {
+    let out = toml::to_string(&config).map_err(io::Error::other)?;
+    std::fs::write(path, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(source: &str) -> SkinChangerConfig {
+        toml::from_str(source).unwrap()
+    }
+
+    fn paint_kit(config: &SkinChangerConfig, weapon: Weapon) -> Option<i32> {
+        config.skins.get(&weapon).map(|skin| skin.paint_kit.id())
+    }
+
+    const PROFILES: &str = r#"
+        enabled = true
+        active_profile = "faceit"
+        [skins.ak47]
+        paint_kit = 1
+        [skins.awp]
+        paint_kit = 2
+        [profiles.faceit]
+        enabled = false
+        rules = []
+        [profiles.faceit.skins.ak47]
+        paint_kit = 10
+        [profiles.faceit_ct]
+        inherits = "faceit"
+        [profiles.faceit_ct.skins.ak47]
+        paint_kit = 20
+        # the name before m4a4, still accepted
+        [profiles.faceit_ct.skins.m4_a4]
+        paint_kit = 21
+        [[rules]]
+        team = "ct"
+        loadout = "ct"
+    "#;
+
+    #[test]
+    fn profiles_override_what_they_inherit() {
+        let file = config(PROFILES);
+        let config = file.resolve_profile(Some("faceit_ct")).unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("faceit_ct"));
+        // the nearest profile wins, the rest is inherited
+        assert_eq!(paint_kit(&config, Weapon::Ak47), Some(20));
+        assert_eq!(paint_kit(&config, Weapon::M4A4), Some(21));
+        assert_eq!(paint_kit(&config, Weapon::Awp), Some(2));
+        assert!(!config.enabled);
+        assert!(config.rules.is_empty());
+
+        // without a name the file's active_profile
+        let config = file.resolve_profile(None).unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("faceit"));
+        assert_eq!(paint_kit(&config, Weapon::Ak47), Some(10));
+        assert_eq!(
+            paint_kit(&config, Weapon::M4A4),
+            paint_kit(&file, Weapon::M4A4)
+        );
+
+        let base = file.resolve_profile(Some(BASE_PROFILE)).unwrap();
+        assert_eq!(base.active_profile, None);
+        assert_eq!(paint_kit(&base, Weapon::Ak47), Some(1));
+        assert!(base.enabled);
+        assert_eq!(base.rules.len(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_profiles_and_cycles() {
+        let file = config(PROFILES);
+        assert!(matches!(
+            file.resolve_profile(Some("esea")),
+            Err(ProfileError::Unknown(name)) if name == "esea"
+        ));
+
+        let file = config(
+            r#"
+            [profiles.a]
+            inherits = "b"
+            [profiles.b]
+            inherits = "c"
+            [profiles.c]
+            inherits = "a"
+            [profiles.d]
+            inherits = "missing"
+            "#,
+        );
+        assert!(matches!(
+            file.resolve_profile(Some("a")),
+            Err(ProfileError::Cycle(chain)) if chain == ["a", "b", "c", "a"]
+        ));
+        assert!(matches!(
+            file.resolve_profile(Some("d")),
+            Err(ProfileError::Unknown(name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn changed_weapons_compares_skins_loadouts_and_rules() {
+        let base = r#"
+            [skins.ak47]
+            paint_kit = 1
+            [loadouts.ct.skins.m4a4]
+            paint_kit = 2
+            [loadouts.ct.skins.knife]
+            paint_kit = 3
+            [[rules]]
+            team = "ct"
+            loadout = "ct"
+        "#;
+        let changed = |edited: &str| {
+            let old = config(base);
+            let new = config(&base.replace("paint_kit = 1", edited));
+            let mut changed: Vec<Weapon> = old.changed_weapons(&new).into_iter().collect();
+            changed.sort_by_key(|weapon| weapon.to_string());
+            changed
+        };
+
+        assert_eq!(changed("paint_kit = 1"), []);
+        assert_eq!(changed("paint_kit = 1\nseed = 5"), [Weapon::Ak47]);
+        assert_eq!(
+            changed("paint_kit = 1\n[skins.awp]\npaint_kit = 4"),
+            [Weapon::Awp]
+        );
+        assert_eq!(
+            changed("paint_kit = 1\n[loadouts.t.skins.glock]\npaint_kit = 5"),
+            [Weapon::Glock]
+        );
+        // another rule may select any loadout
+        assert_eq!(
+            changed("paint_kit = 1\n[[rules]]\nloadout = \"ct\""),
+            [Weapon::Knife, Weapon::M4A4]
+        );
+
+        let old = config(base);
+        let mut new = old.clone();
+        let ct = new.loadouts.get_mut("ct").unwrap();
+        ct.skins.remove(&Weapon::M4A4);
+        assert_eq!(old.changed_weapons(&new), HashSet::from([Weapon::M4A4]));
+    }
+
+    #[test]
+    fn readme_examples_parse() {
+        let readme = include_str!("../../README.md");
+        let mut blocks = 0;
+        for block in readme.split("```toml\n").skip(1) {
+            let source = block.split("```").next().unwrap();
+            if let Err(e) = toml::from_str::<SkinChangerConfig>(source) {
+                panic!("README example does not parse: {e}\n{source}");
+            }
+            blocks += 1;
+        }
+        assert!(blocks > 0);
+    }
+}
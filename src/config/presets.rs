@@ -0,0 +1,208 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{config::PaintKitRef, cs2::entity::weapon::Weapon, game_data::lookup::suggest};
+
+/// File name of the preset library in the config directory.
+pub const PRESETS_NAME: &str = "presets.toml";
+
+/// Written to the config directory when the library does not exist there.
+const DEFAULT_PRESETS: &str = include_str!("presets.toml");
+
+/// Phase and pattern names for skin entries, from `presets.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Presets {
+    /// Finish -> weapon -> phase -> paint kit
+    phases: BTreeMap<String, HashMap<Weapon, BTreeMap<String, i32>>>,
+    /// Pattern -> weapon -> paint kit and seed
+    patterns: BTreeMap<String, HashMap<Weapon, Pattern>>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Pattern {
+    pub paint_kit: i32,
+    pub seed: i32,
+}
+
+impl Presets {
+    /// The library shipped with this build.
+    pub fn builtin() -> Self {
+        toml::from_str(DEFAULT_PRESETS).expect("built-in presets parse")
+    }
+
+    /// Writes the built-in library to `path` when there is no file there.
+    pub fn write_default(path: &Path) {
+        if path.exists() {
+            return;
+        }
+        match std::fs::write(path, DEFAULT_PRESETS) {
+            Ok(()) => log::info!("wrote preset library {path:?}"),
+            Err(e) => log::warn!("could not write preset library {path:?}: {e}"),
+        }
+    }
+
+    /// Reads the library at `path`, the built-in one when it does not exist.
+    /// Errors carry the line when there is one.
+    pub fn load(path: &Path) -> Result<Self, (Option<usize>, String)> {
+        if !path.exists() {
+            return Ok(Self::builtin());
+        }
+        let source = std::fs::read_to_string(path)
+            .map_err(|e| (None, format!("could not read file: {e}")))?;
+        toml::from_str(&source).map_err(|e: toml::de::Error| {
+            let line = e
+                .span()
+                .map(|span| source[..span.start].matches('\n').count() + 1);
+            (line, e.message().to_string())
+        })
+    }
+
+    /// Paint kit of `phase` for `weapon`. `paint_kit` picks the finish when
+    /// the phase exists in several: a finish name such as `"Gamma Doppler"`,
+    /// or the ID of another phase of it.
+    pub fn phase(
+        &self,
+        weapon: &Weapon,
+        paint_kit: &PaintKitRef,
+        phase: &str,
+    ) -> Result<i32, String> {
+        let finishes: Vec<(&String, &BTreeMap<String, i32>)> = self
+            .phases
+            .iter()
+            .filter_map(|(finish, weapons)| Some((finish, entry(weapons, weapon)?)))
+            .collect();
+        let chosen: Vec<_> = match paint_kit {
+            PaintKitRef::Name(name) => {
+                let name = name.rsplit('|').next().unwrap_or(name);
+                let key = normalize(name);
+                finishes
+                    .iter()
+                    .filter(|(finish, _)| **finish == key)
+                    .collect()
+            }
+            PaintKitRef::Id(id) => finishes
+                .iter()
+                .filter(|(_, phases)| phases.values().any(|kit| kit == id))
+                .collect(),
+        };
+        // an unrelated paint kit does not narrow the search
+        let candidates = if chosen.is_empty() {
+            finishes.iter().collect()
+        } else {
+            chosen
+        };
+
+        let wanted = normalize(phase);
+        let matches: Vec<(&String, i32)> = candidates
+            .iter()
+            .filter_map(|(finish, phases)| Some((*finish, *phases.get(&wanted)?)))
+            .collect();
+        match matches.as_slice() {
+            [(_, id)] => Ok(*id),
+            [] if finishes.is_empty() => Err(format!("no phases for the {weapon}")),
+            [] => {
+                let suggestions = suggest(
+                    &wanted,
+                    candidates
+                        .iter()
+                        .flat_map(|(_, phases)| phases.keys().map(String::as_str)),
+                );
+                Err(unknown("phase", phase, suggestions))
+            }
+            matches => {
+                let finishes: Vec<&str> =
+                    matches.iter().map(|(finish, _)| finish.as_str()).collect();
+                Err(format!(
+                    "{phase:?} is a phase of {}, set paint_kit to the finish",
+                    finishes.join(" and ")
+                ))
+            }
+        }
+    }
+
+    /// Paint kit and seed of `pattern` on `weapon`.
+    pub fn pattern(&self, weapon: &Weapon, pattern: &str) -> Result<Pattern, String> {
+        let wanted = normalize(pattern);
+        match self.patterns.get(&wanted) {
+            Some(weapons) => entry(weapons, weapon)
+                .copied()
+                .ok_or_else(|| format!("{pattern:?} is not a pattern of the {weapon}")),
+            None => {
+                let suggestions = suggest(&wanted, self.patterns.keys().map(String::as_str));
+                Err(unknown("pattern", pattern, suggestions))
+            }
+        }
+    }
+}
+
+/// The entry for `weapon`, or for any knife when a knife model has none.
+fn entry<'a, T>(weapons: &'a HashMap<Weapon, T>, weapon: &Weapon) -> Option<&'a T> {
+    weapons
+        .get(weapon)
+        .or_else(|| weapons.get(&weapon.fallback()?))
+}
+
+/// `"Gamma Doppler"`, `"Phase 1"` -> `gamma_doppler`, `phase_1`
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+fn unknown(kind: &str, name: &str, suggestions: Vec<String>) -> String {
+    if suggestions.is_empty() {
+        return format!("unknown {kind} {name:?} in {PRESETS_NAME}");
+    }
+    let suggestions: Vec<String> = suggestions.iter().map(|s| format!("{s:?}")).collect();
+    format!(
+        "unknown {kind} {name:?} in {PRESETS_NAME}, did you mean {}?",
+        suggestions.join(" or ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_library_is_not_written() {
+        let path =
+            std::env::temp_dir().join(format!("presets-{}-missing.toml", std::process::id()));
+        let presets = Presets::load(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            presets.phase(&Weapon::Karambit, &PaintKitRef::Id(0), "ruby"),
+            Ok(415)
+        );
+
+        Presets::write_default(&path);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), DEFAULT_PRESETS);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn shared_phase_needs_the_finish() {
+        let presets = Presets::builtin();
+        let knife = Weapon::Karambit;
+        assert!(
+            presets
+                .phase(&knife, &PaintKitRef::Id(0), "Phase 1")
+                .unwrap_err()
+                .contains("set paint_kit to the finish")
+        );
+        let gamma = PaintKitRef::Name("Karambit | Gamma Doppler".to_string());
+        assert_eq!(presets.phase(&knife, &gamma, "Phase 1"), Ok(569));
+        assert_eq!(
+            presets.phase(&knife, &PaintKitRef::Id(415), "phase_1"),
+            Ok(418)
+        );
+        assert!(
+            presets
+                .phase(&Weapon::Ak47, &PaintKitRef::Id(0), "ruby")
+                .is_err()
+        );
+    }
+}
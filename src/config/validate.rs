@@ -0,0 +1,520 @@
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use toml::{
+    Table, Value,
+    de::{DeTable, DeValue},
+};
+
+use crate::{
+    config::{
+        BASE_PROFILE, CONFIG_PATH, CONFIG_VERSION, Loadout, PRESETS_NAME, PaintKitRef, Presets,
+        ProfileError, SkinChangerConfig, WeaponSkinConfig, Wear, migrate, wear,
+    },
+    cs2::entity::weapon::Weapon,
+    game_data::{self, GameData, lookup},
+};
+
+pub const MAX_SEED: i32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a config file.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: Option<usize>,
+    /// Dotted key path, empty for problems with the file as a whole.
+    pub key: String,
+    pub reason: String,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn log(&self) {
+        match self.severity {
+            Severity::Error => log::error!("{self}"),
+            Severity::Warning => log::warn!("{self}"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        if self.key.is_empty() {
+            write!(f, ": {severity}: {}", self.reason)
+        } else {
+            write!(f, ": {severity}: {}: {}", self.key, self.reason)
+        }
+    }
+}
+
+/// Parses and checks the config file at `path`. The config is only returned
+/// when no diagnostic is an error. Names are checked against game data from
+/// the file's `game_dir` in its `language`.
+pub fn validate_config(path: &Path) -> (Option<SkinChangerConfig>, Vec<Diagnostic>) {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => {
+            let diagnostic = Diagnostic {
+                severity: Severity::Error,
+                file: path.to_path_buf(),
+                line: None,
+                key: String::new(),
+                reason: format!("could not read file: {e}"),
+            };
+            return (None, vec![diagnostic]);
+        }
+    };
+    let mut diagnostics = Diagnostics {
+        file: path.to_path_buf(),
+        source: &source,
+        table: None,
+        list: Vec::new(),
+    };
+
+    diagnostics.table = DeTable::parse(&source).ok().map(|table| table.into_inner());
+
+    let config: SkinChangerConfig = match toml::from_str(&source) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.span().map(|span| diagnostics.line(span));
+            // type errors point at a value in a file that parsed, syntax errors have no key
+            let key = e
+                .span()
+                .and_then(|span| diagnostics.key_at(span))
+                .unwrap_or_default();
+            diagnostics.push(Severity::Error, line, key, e.message().to_string());
+            return (None, diagnostics.list);
+        }
+    };
+
+    let game_data = game_data::with_settings(&config.game_dir, &config.language);
+
+    if let Ok(table) = source.parse::<Table>() {
+        check_version(&table, &mut diagnostics);
+        if let Ok(Value::Table(known)) = Value::try_from(&config) {
+            check_unknown_keys(
+                &Value::Table(table),
+                &Value::Table(known),
+                &mut Vec::new(),
+                &mut diagnostics,
+            );
+        }
+    }
+    let presets_path = CONFIG_PATH.join(PRESETS_NAME);
+    let presets = Presets::load(&presets_path).unwrap_or_else(|(line, e)| {
+        diagnostics.list.push(Diagnostic {
+            severity: Severity::Warning,
+            file: presets_path,
+            line,
+            key: String::new(),
+            reason: format!("{e}, using the built-in presets"),
+        });
+        Presets::builtin()
+    });
+    let names = Names {
+        presets: &presets,
+        game_data: &game_data,
+    };
+    check(&config, &names, &mut diagnostics);
+
+    let list = diagnostics.list;
+    let mut config = (!list.iter().any(Diagnostic::is_error)).then_some(config);
+    if let Some(config) = &mut config {
+        config.resolve_skins(&presets, &game_data);
+    }
+    (config, list)
+}
+
+struct Diagnostics<'a> {
+    file: PathBuf,
+    source: &'a str,
+    table: Option<DeTable<'a>>,
+    list: Vec<Diagnostic>,
+}
+
+impl Diagnostics<'_> {
+    fn line(&self, span: Range<usize>) -> usize {
+        let start = span.start.min(self.source.len());
+        self.source[..start].matches('\n').count() + 1
+    }
+
+    /// Line of the value at `path`, or of its closest parent that exists.
+    /// Array elements are addressed by their index.
+    fn line_of(&self, path: &[String]) -> Option<usize> {
+        let (first, rest) = path.split_first()?;
+        let mut value = self.table.as_ref()?.get(first.as_str())?;
+        for key in rest {
+            let next = match (key.parse::<usize>(), value.get_ref()) {
+                (Ok(index), DeValue::Array(_)) => value.get_ref().get(index),
+                _ => value.get_ref().get(key.as_str()),
+            };
+            match next {
+                Some(next) => value = next,
+                None => break,
+            }
+        }
+        Some(self.line(value.span()))
+    }
+
+    /// Dotted path of the value at `span`. Table spans only cover their
+    /// header, so every table is searched.
+    fn key_at(&self, span: Range<usize>) -> Option<String> {
+        fn find(table: &DeTable<'_>, span: &Range<usize>, path: &mut Vec<String>) -> bool {
+            for (key, value) in table.iter() {
+                path.push(key.get_ref().to_string());
+                let contains =
+                    |outer: Range<usize>| outer.start <= span.start && span.end <= outer.end;
+                let found = contains(key.span())
+                    || match value.get_ref() {
+                        DeValue::Table(inner) => find(inner, span, path),
+                        _ => contains(value.span()),
+                    };
+                if found {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+
+        let mut path = Vec::new();
+        find(self.table.as_ref()?, &span, &mut path).then(|| path.join("."))
+    }
+
+    fn push(&mut self, severity: Severity, line: Option<usize>, key: String, reason: String) {
+        self.list.push(Diagnostic {
+            severity,
+            file: self.file.clone(),
+            line,
+            key,
+            reason,
+        });
+    }
+
+    fn report(&mut self, severity: Severity, path: &[String], reason: impl Into<String>) {
+        let line = self.line_of(path);
+        self.push(severity, line, path.join("."), reason.into());
+    }
+}
+
+fn path(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+fn check_version(table: &Table, diagnostics: &mut Diagnostics<'_>) {
+    match migrate::version(table) {
+        Some(version) if version > CONFIG_VERSION => diagnostics.report(
+            Severity::Error,
+            &path(&["version"]),
+            format!("{version} is newer than this build supports ({CONFIG_VERSION})"),
+        ),
+        Some(version) if version < CONFIG_VERSION => diagnostics.report(
+            Severity::Warning,
+            &path(&["version"]),
+            format!("{version} is migrated to {CONFIG_VERSION} when the skin changer starts"),
+        ),
+        Some(_) => {}
+        None => diagnostics.report(
+            Severity::Error,
+            &path(&["version"]),
+            "not a positive integer",
+        ),
+    }
+}
+
+/// Warns about every key in `source` that is not in `known`, the parsed
+/// config serialized again. Such keys are ignored by the defaults, usually a
+/// typo or a key from another version.
+fn check_unknown_keys(
+    source: &Value,
+    known: &Value,
+    path: &mut Vec<String>,
+    diagnostics: &mut Diagnostics<'_>,
+) {
+    match (source, known) {
+        (Value::Table(source), Value::Table(known)) => {
+            for (key, value) in source {
+                path.push(key.clone());
+                match known.get(key) {
+                    Some(known) => check_unknown_keys(value, known, path, diagnostics),
+                    None => diagnostics.report(Severity::Warning, path, "unknown key, ignored"),
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(source), Value::Array(known)) => {
+            for (index, (value, known)) in source.iter().zip(known).enumerate() {
+                path.push(index.to_string());
+                check_unknown_keys(value, known, path, diagnostics);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Where skin entries look up names: phases and patterns, paint kits.
+struct Names<'a> {
+    presets: &'a Presets,
+    game_data: &'a GameData,
+}
+
+fn check(config: &SkinChangerConfig, names: &Names<'_>, diagnostics: &mut Diagnostics<'_>) {
+    check_skins(&config.skins, &path(&["skins"]), names, diagnostics);
+    check_loadouts(&config.loadouts, &path(&["loadouts"]), names, diagnostics);
+
+    for (index, rule) in config.rules.iter().enumerate() {
+        if !config.loadouts.contains_key(&rule.loadout) {
+            diagnostics.report(
+                Severity::Error,
+                &path(&["rules"]),
+                format!(
+                    "rule {} selects unknown loadout {:?}",
+                    index + 1,
+                    rule.loadout
+                ),
+            );
+        }
+    }
+
+    for (name, profile) in &config.profiles {
+        let base = path(&["profiles", name]);
+        check_skins(
+            &profile.skins,
+            &[base.clone(), path(&["skins"])].concat(),
+            names,
+            diagnostics,
+        );
+        check_loadouts(
+            &profile.loadouts,
+            &[base.clone(), path(&["loadouts"])].concat(),
+            names,
+            diagnostics,
+        );
+        if let Some(parent) = &profile.inherits
+            && !config.profiles.contains_key(parent)
+        {
+            diagnostics.report(
+                Severity::Error,
+                &[base.clone(), path(&["inherits"])].concat(),
+                format!("unknown profile {parent:?}"),
+            );
+        }
+        if let Err(e @ ProfileError::Cycle(_)) = config.resolve_profile(Some(name)) {
+            diagnostics.report(Severity::Error, &base, e.to_string());
+        }
+        if let Some(rules) = &profile.rules {
+            let loadouts = &profile.loadouts;
+            for (index, rule) in rules.iter().enumerate() {
+                // inherited loadouts are only known once the profile is resolved
+                let known = loadouts.contains_key(&rule.loadout)
+                    || config.loadouts.contains_key(&rule.loadout)
+                    || config
+                        .profiles
+                        .values()
+                        .any(|p| p.loadouts.contains_key(&rule.loadout));
+                if !known {
+                    diagnostics.report(
+                        Severity::Error,
+                        &[base.clone(), path(&["rules"])].concat(),
+                        format!(
+                            "rule {} selects unknown loadout {:?}",
+                            index + 1,
+                            rule.loadout
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(active) = &config.active_profile
+        && active != BASE_PROFILE
+        && !config.profiles.contains_key(active)
+    {
+        diagnostics.report(
+            Severity::Error,
+            &path(&["active_profile"]),
+            format!("unknown profile {active:?}"),
+        );
+    }
+}
+
+fn check_loadouts(
+    loadouts: &HashMap<String, Loadout>,
+    base: &[String],
+    names: &Names<'_>,
+    diagnostics: &mut Diagnostics<'_>,
+) {
+    let mut loadouts: Vec<_> = loadouts.iter().collect();
+    loadouts.sort_by_key(|(name, _)| *name);
+    for (name, loadout) in loadouts {
+        let skins = [base.to_vec(), path(&[name, "skins"])].concat();
+        check_skins(&loadout.skins, &skins, names, diagnostics);
+    }
+}
+
+fn check_skins(
+    skins: &HashMap<Weapon, WeaponSkinConfig>,
+    base: &[String],
+    names: &Names<'_>,
+    diagnostics: &mut Diagnostics<'_>,
+) {
+    let Names { presets, game_data } = names;
+    let mut skins: Vec<_> = skins.iter().collect();
+    skins.sort_by_key(|(weapon, _)| weapon.as_ref().to_string());
+    for (weapon, skin) in skins {
+        let key = |field: &str| [base.to_vec(), path(&[weapon.as_ref(), field])].concat();
+
+        if *weapon == Weapon::Unknown {
+            diagnostics.report(
+                Severity::Warning,
+                &[base.to_vec(), path(&[weapon.as_ref()])].concat(),
+                "not a weapon, ignored",
+            );
+        }
+        if !(0..=MAX_SEED).contains(&skin.seed) {
+            diagnostics.report(
+                Severity::Error,
+                &key("seed"),
+                format!("{} is outside 0 to {MAX_SEED}", skin.seed),
+            );
+        }
+        if skin.stattrak < -1 {
+            diagnostics.report(
+                Severity::Error,
+                &key("stattrak"),
+                format!("{} is below -1 (-1 disables StatTrak)", skin.stattrak),
+            );
+        }
+        // a phase or pattern replaces the paint kit, which may name the finish
+        let mut paint_kit = skin.paint_kit.clone();
+        if let Some(phase) = &skin.phase {
+            match presets.phase(weapon, &skin.paint_kit, phase) {
+                Ok(id) => paint_kit = PaintKitRef::Id(id),
+                Err(e) => diagnostics.report(Severity::Error, &key("phase"), e),
+            }
+        }
+        if let Some(pattern) = &skin.pattern {
+            if skin.phase.is_some() {
+                diagnostics.report(
+                    Severity::Error,
+                    &key("pattern"),
+                    "set together with phase, use one of them",
+                );
+            }
+            match presets.pattern(weapon, pattern) {
+                Ok(pattern) => {
+                    if skin.seed != 0 && skin.seed != pattern.seed {
+                        diagnostics.report(
+                            Severity::Warning,
+                            &key("seed"),
+                            format!("replaced by the pattern's seed {}", pattern.seed),
+                        );
+                    }
+                    paint_kit = PaintKitRef::Id(pattern.paint_kit);
+                }
+                Err(e) => diagnostics.report(Severity::Error, &key("pattern"), e),
+            }
+        }
+        let paint_kit = match &paint_kit {
+            PaintKitRef::Name(name) => match lookup::find_paint_kit(game_data, weapon, name) {
+                Ok(id) => id as i32,
+                Err(e) => {
+                    diagnostics.report(Severity::Error, &key("paint_kit"), e.to_string());
+                    0
+                }
+            },
+            PaintKitRef::Id(id) if *id < 0 => {
+                diagnostics.report(
+                    Severity::Error,
+                    &key("paint_kit"),
+                    format!("{id} is negative"),
+                );
+                *id
+            }
+            // a phase or pattern that failed is already reported
+            PaintKitRef::Id(0)
+                if skin.enabled && skin.phase.is_none() && skin.pattern.is_none() =>
+            {
+                diagnostics.report(
+                    Severity::Warning,
+                    &key("paint_kit"),
+                    "enabled without a paint kit, nothing is applied",
+                );
+                0
+            }
+            PaintKitRef::Id(id) => {
+                if let Some(items) = game_data.items()
+                    && items.paint_kit(*id as u32).is_none()
+                {
+                    diagnostics.report(
+                        Severity::Warning,
+                        &key("paint_kit"),
+                        format!("{id} is not a paint kit in items_game.txt"),
+                    );
+                } else if lookup::paint_kit_available(game_data, weapon, *id as u32) == Some(false) {
+                    diagnostics.report(
+                        Severity::Warning,
+                        &key("paint_kit"),
+                        format!("{id} is not available for the {weapon}"),
+                    );
+                }
+                *id
+            }
+        };
+        match skin.wear {
+            Wear::Float(wear) if !(0.0..=1.0).contains(&wear) => diagnostics.report(
+                Severity::Error,
+                &key("wear"),
+                format!("{wear} is outside 0.0 to 1.0"),
+            ),
+            wear => {
+                let (min, max) = wear::float_range(game_data, paint_kit);
+                match wear.resolve((min, max)) {
+                    Err(e) => diagnostics.report(Severity::Error, &key("wear"), e),
+                    Ok(resolved) if matches!(wear, Wear::Float(wear) if wear != resolved) => {
+                        diagnostics.report(
+                            Severity::Warning,
+                            &key("wear"),
+                            format!(
+                                "{wear} is outside the paint kit's float range {min:.2} to {max:.2}, clamped to {resolved}"
+                            ),
+                        )
+                    }
+                    Ok(_) => {}
+                }
+            }
+        }
+        // m_szCustomName is a char[161]
+        if skin.name_tag.len() > 160 {
+            diagnostics.report(
+                Severity::Warning,
+                &key("name_tag"),
+                format!("{} bytes long, cut to 160", skin.name_tag.len()),
+            );
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Deserializer, Serialize, de};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+
+use crate::game_data::{self, GameData};
+
+/// Exterior shown in game, by wear float.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AsRefStr, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Condition {
+    FactoryNew,
+    MinimalWear,
+    FieldTested,
+    WellWorn,
+    BattleScarred,
+}
+
+impl Condition {
+    /// Floats of this condition, from inclusive to exclusive (1.0 included
+    /// for battle scarred).
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            Condition::FactoryNew => (0.0, 0.07),
+            Condition::MinimalWear => (0.07, 0.15),
+            Condition::FieldTested => (0.15, 0.38),
+            Condition::WellWorn => (0.38, 0.45),
+            Condition::BattleScarred => (0.45, 1.0),
+        }
+    }
+
+    pub fn from_float(wear: f32) -> Self {
+        Self::iter()
+            .find(|condition| wear < condition.range().1)
+            .unwrap_or(Condition::BattleScarred)
+    }
+
+    fn english_name(self) -> &'static str {
+        match self {
+            Condition::FactoryNew => "Factory New",
+            Condition::MinimalWear => "Minimal Wear",
+            Condition::FieldTested => "Field-Tested",
+            Condition::WellWorn => "Well-Worn",
+            Condition::BattleScarred => "Battle-Scarred",
+        }
+    }
+}
+
+impl Display for Condition {
+    /// Localized name from the game files, or the built-in English name.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let token = format!("SFUI_InvTooltip_Wear_Amount_{}", *self as u8);
+        let game_data = game_data::current();
+        let name = game_data.localize(&token).unwrap_or(self.english_name());
+        write!(f, "{name}")
+    }
+}
+
+/// An exact wear float, or a condition that picks the lowest float the
+/// paint kit allows in it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Wear {
+    Float(f32),
+    Condition(Condition),
+}
+
+impl Default for Wear {
+    fn default() -> Self {
+        Wear::Float(0.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Wear {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = Wear;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let conditions: Vec<String> =
+                    Condition::iter().map(|c| c.as_ref().to_string()).collect();
+                write!(f, "a float or one of {}", conditions.join(", "))
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Wear, E> {
+                Ok(Wear::Float(value as f32))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Wear, E> {
+                Ok(Wear::Float(value as f32))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Wear, E> {
+                Condition::iter()
+                    .find(|condition| condition.as_ref() == value)
+                    .map(Wear::Condition)
+                    .ok_or_else(|| E::invalid_value(de::Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl Wear {
+    /// The float, the lowest of the condition for unresolved conditions.
+    pub fn value(&self) -> f32 {
+        match self {
+            Wear::Float(wear) => *wear,
+            Wear::Condition(condition) => condition.range().0,
+        }
+    }
+
+    /// Fits the wear into a paint kit's `min..=max` float range. Floats are
+    /// clamped, a condition the range does not reach is an error. An
+    /// inverted range resolves to its `max`.
+    pub fn resolve(&self, (min, max): (f32, f32)) -> Result<f32, String> {
+        match self {
+            Wear::Float(wear) => Ok(wear.max(min).min(max)),
+            Wear::Condition(condition) => {
+                let (low, high) = condition.range();
+                let (low, high) = (low.max(min), high.min(max));
+                if low < high || (low == high && *condition == Condition::BattleScarred) {
+                    Ok(low)
+                } else {
+                    Err(format!(
+                        "{condition} is outside the paint kit's float range {min:.2} to {max:.2}"
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl Display for Wear {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Wear::Float(wear) => write!(f, "{wear}"),
+            Wear::Condition(condition) => write!(f, "{condition}"),
+        }
+    }
+}
+
+/// Float range of a paint kit, 0.0 to 1.0 without one or when it is not in
+/// the item database.
+pub fn float_range(game_data: &GameData, paint_kit: i32) -> (f32, f32) {
+    game_data
+        .items()
+        .filter(|_| paint_kit > 0)
+        .and_then(|items| items.paint_kit(paint_kit as u32))
+        .map(|kit| (kit.min_float, kit.max_float))
+        .unwrap_or((0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::items::ItemDatabase;
+
+    /// Float range of paint kit `id` in the test item database.
+    fn range(id: u32) -> (f32, f32) {
+        let items =
+            ItemDatabase::parse(include_str!("../game_data/testdata/items_game.txt")).unwrap();
+        let kit = items.paint_kit(id).unwrap();
+        (kit.min_float, kit.max_float)
+    }
+
+    const ASIIMOV: u32 = 279;
+    const FADE: u32 = 38;
+
+    #[test]
+    fn conditions_pick_the_lowest_float_in_range() {
+        let cases = [
+            (Condition::FieldTested, ASIIMOV, 0.18),
+            (Condition::BattleScarred, ASIIMOV, 0.45),
+            (Condition::MinimalWear, FADE, 0.07),
+            (Condition::FactoryNew, FADE, 0.0),
+        ];
+        for (condition, kit, expected) in cases {
+            let wear = Wear::Condition(condition).resolve(range(kit));
+            assert_eq!(wear, Ok(expected), "{condition:?} on {kit}");
+        }
+    }
+
+    #[test]
+    fn conditions_outside_the_range_are_errors() {
+        for outside in [Condition::FactoryNew, Condition::MinimalWear] {
+            let error = Wear::Condition(outside)
+                .resolve(range(ASIIMOV))
+                .unwrap_err();
+            assert!(error.contains("0.18 to 1.00"), "{error}");
+        }
+        for outside in [Condition::FieldTested, Condition::BattleScarred] {
+            assert!(Wear::Condition(outside).resolve(range(FADE)).is_err());
+        }
+        // only battle scarred includes its upper end
+        assert_eq!(
+            Wear::Condition(Condition::BattleScarred).resolve((1.0, 1.0)),
+            Ok(1.0)
+        );
+        assert!(
+            Wear::Condition(Condition::WellWorn)
+                .resolve((0.45, 1.0))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn floats_are_clamped() {
+        assert_eq!(Wear::Float(0.01).resolve(range(ASIIMOV)), Ok(0.18));
+        assert_eq!(Wear::Float(0.5).resolve(range(FADE)), Ok(0.08));
+        assert_eq!(Wear::Float(0.3).resolve(range(ASIIMOV)), Ok(0.3));
+        // an inverted range does not panic
+        assert_eq!(Wear::Float(0.5).resolve((0.8, 0.2)), Ok(0.2));
+        assert!(
+            Wear::Condition(Condition::FieldTested)
+                .resolve((0.8, 0.2))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn conditions_by_float() {
+        assert_eq!(Condition::from_float(0.0), Condition::FactoryNew);
+        assert_eq!(Condition::from_float(0.07), Condition::MinimalWear);
+        assert_eq!(Condition::from_float(0.449), Condition::WellWorn);
+        assert_eq!(Condition::from_float(1.0), Condition::BattleScarred);
+    }
+
+    #[test]
+    fn parses_floats_and_conditions() {
+        #[derive(Deserialize)]
+        struct Entry {
+            wear: Wear,
+        }
+        let wear = |source: &str| toml::from_str::<Entry>(source).map(|entry| entry.wear);
+        assert_eq!(wear("wear = 0.25").unwrap(), Wear::Float(0.25));
+        assert_eq!(wear("wear = 1").unwrap(), Wear::Float(1.0));
+        assert_eq!(
+            wear("wear = \"well_worn\"").unwrap(),
+            Wear::Condition(Condition::WellWorn)
+        );
+        assert!(wear("wear = \"worn\"").is_err());
+    }
+}
@@ -10,6 +10,10 @@ pub mod cs2 {
     pub const LIBS: [&str; 6] = [
         CLIENT_LIB, ENGINE_LIB, TIER0_LIB, INPUT_LIB, SDL_LIB, SCHEMA_LIB,
     ];
+
+    /// Windows module names, used when a cs2-dumper file has no linux keys.
+    pub const WINDOWS_MODULES: [(&str, &str); 2] =
+        [(CLIENT_LIB, "client.dll"), (ENGINE_LIB, "engine2.dll")];
 }
 
 pub mod elf {
@@ -22,4 +26,7 @@ pub mod elf {
     pub const SECTION_HEADER_NUM_ENTRIES: u64 = 0x3C;
 
     pub const DYNAMIC_SECTION_PHT_TYPE: u64 = 0x02;
+    pub const NOTE_SEGMENT_PHT_TYPE: u64 = 0x04;
+
+    pub const NOTE_GNU_BUILD_ID: u32 = 0x03;
 }
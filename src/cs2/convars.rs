@@ -0,0 +1,199 @@
+use std::fmt::Display;
+
+use crate::cs2::CS2;
+
+/// Sanity limit for the ConVar count, the client registers a few thousand.
+const MAX_CONVARS: u64 = 0x4000;
+
+/// `EConVarType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConVarType {
+    Bool,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Float32,
+    Float64,
+    String,
+    Color,
+    Vector2,
+    Vector3,
+    Vector4,
+    QAngle,
+    Unknown(i16),
+}
+
+impl ConVarType {
+    fn from_raw(raw: i16) -> Self {
+        match raw {
+            0 => ConVarType::Bool,
+            1 => ConVarType::Int16,
+            2 => ConVarType::UInt16,
+            3 => ConVarType::Int32,
+            4 => ConVarType::UInt32,
+            5 => ConVarType::Int64,
+            6 => ConVarType::UInt64,
+            7 => ConVarType::Float32,
+            8 => ConVarType::Float64,
+            9 => ConVarType::String,
+            10 => ConVarType::Color,
+            11 => ConVarType::Vector2,
+            12 => ConVarType::Vector3,
+            13 => ConVarType::Vector4,
+            14 => ConVarType::QAngle,
+            _ => ConVarType::Unknown(raw),
+        }
+    }
+}
+
+impl Display for ConVarType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConVarType::Unknown(raw) => write!(f, "unknown({raw})"),
+            ty => write!(f, "{}", format!("{ty:?}").to_lowercase()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConVarValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    String(String),
+    Color([u8; 4]),
+    Vector(Vec<f32>),
+    Unknown,
+}
+
+impl ConVarValue {
+    /// The value as an integer, for bools and integer types.
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            ConVarValue::Bool(value) => Some(*value as i64),
+            ConVarValue::Int(value) => Some(*value),
+            ConVarValue::UInt(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ConVarValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConVarValue::Bool(value) => write!(f, "{value}"),
+            ConVarValue::Int(value) => write!(f, "{value}"),
+            ConVarValue::UInt(value) => write!(f, "{value}"),
+            ConVarValue::Float(value) => write!(f, "{value}"),
+            ConVarValue::String(value) => write!(f, "\"{value}\""),
+            ConVarValue::Color([r, g, b, a]) => write!(f, "{r} {g} {b} {a}"),
+            ConVarValue::Vector(values) => {
+                let values: Vec<String> = values.iter().map(f32::to_string).collect();
+                write!(f, "{}", values.join(" "))
+            }
+            ConVarValue::Unknown => write!(f, "?"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConVar {
+    pub name: String,
+    pub description: String,
+    pub flags: u64,
+    pub ty: ConVarType,
+    pub value: ConVarValue,
+}
+
+impl CS2 {
+    /// Addresses of every registered ConVar.
+    fn convar_addresses(&self) -> Vec<u64> {
+        let offsets = &self.offsets.convar;
+        let cvar = self.offsets.interface.cvar;
+        let list: u64 = self.process.read(cvar + offsets.list);
+        let count = self.process.read::<u32>(cvar + offsets.count) as u64;
+        if list == 0 || count > MAX_CONVARS {
+            log::debug!("convar list 0x{list:X} with {count} entries looks invalid");
+            return Vec::new();
+        }
+        (0..count)
+            .map(|i| self.process.read::<u64>(list + i * offsets.entry_size))
+            .filter(|&convar| convar != 0)
+            .collect()
+    }
+
+    fn convar_name(&self, convar: u64) -> String {
+        let name: u64 = self.process.read(convar + self.offsets.convar.name);
+        if name == 0 {
+            return String::new();
+        }
+        self.process.read_string(name)
+    }
+
+    fn read_convar(&self, convar: u64) -> ConVar {
+        let offsets = &self.offsets.convar;
+        let description: u64 = self.process.read(convar + offsets.description);
+        let ty = ConVarType::from_raw(self.process.read(convar + offsets.ty));
+        ConVar {
+            name: self.convar_name(convar),
+            description: if description == 0 {
+                String::new()
+            } else {
+                self.process.read_string(description)
+            },
+            flags: self.process.read(convar + offsets.flags),
+            ty,
+            value: self.read_convar_value(convar + offsets.value, ty),
+        }
+    }
+
+    fn read_convar_value(&self, address: u64, ty: ConVarType) -> ConVarValue {
+        let read = |address| self.process.read::<[f32; 4]>(address);
+        match ty {
+            ConVarType::Bool => ConVarValue::Bool(self.process.read::<u8>(address) != 0),
+            ConVarType::Int16 => ConVarValue::Int(self.process.read::<i16>(address) as i64),
+            ConVarType::UInt16 => ConVarValue::UInt(self.process.read::<u16>(address) as u64),
+            ConVarType::Int32 => ConVarValue::Int(self.process.read::<i32>(address) as i64),
+            ConVarType::UInt32 => ConVarValue::UInt(self.process.read::<u32>(address) as u64),
+            ConVarType::Int64 => ConVarValue::Int(self.process.read(address)),
+            ConVarType::UInt64 => ConVarValue::UInt(self.process.read(address)),
+            ConVarType::Float32 => ConVarValue::Float(self.process.read::<f32>(address) as f64),
+            ConVarType::Float64 => ConVarValue::Float(self.process.read(address)),
+            ConVarType::String => {
+                let string: u64 = self.process.read(address);
+                ConVarValue::String(if string == 0 {
+                    String::new()
+                } else {
+                    self.process.read_string_uncached(string)
+                })
+            }
+            ConVarType::Color => ConVarValue::Color(self.process.read(address)),
+            ConVarType::Vector2 => ConVarValue::Vector(read(address)[..2].to_vec()),
+            ConVarType::Vector3 | ConVarType::QAngle => {
+                ConVarValue::Vector(read(address)[..3].to_vec())
+            }
+            ConVarType::Vector4 => ConVarValue::Vector(read(address).to_vec()),
+            ConVarType::Unknown(_) => ConVarValue::Unknown,
+        }
+    }
+
+    /// Every registered ConVar with its current value, read-only.
+    pub fn convars(&self) -> Vec<ConVar> {
+        self.convar_addresses()
+            .into_iter()
+            .map(|convar| self.read_convar(convar))
+            .collect()
+    }
+
+    /// Reads the ConVar called `name`, like `game_mode` or `game_type`.
+    pub fn convar(&self, name: &str) -> Option<ConVar> {
+        self.convar_addresses()
+            .into_iter()
+            .find(|&convar| self.convar_name(convar) == name)
+            .map(|convar| self.read_convar(convar))
+    }
+}
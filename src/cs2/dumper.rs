@@ -1,83 +1,251 @@
-use crate::cs2::offsets::Offsets;
-use serde::Deserialize;
-use std::collections::HashMap;
-
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct DumperOutput {
-    #[serde(rename = "client.dll")]
-    client: Option<HashMap<String, u64>>,
-    #[serde(rename = "engine2.dll")]
-    engine: Option<HashMap<String, u64>>,
-    #[serde(rename = "libclient.so")]
-    libclient: Option<HashMap<String, u64>>,
-    #[serde(rename = "libengine2.so")]
-    libengine: Option<HashMap<String, u64>>,
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    config::{OffsetSourcesConfig, SourceKind},
+    constants::cs2,
+    cs2::{
+        find_offsets::{SCHEMA_FIELDS, resolve_schema_offsets},
+        offset_sources::SourceLoader,
+        offsets::{LibraryOffsets, Offsets},
+        schema::Schema,
+    },
+    os::process::Process,
+};
+
+/// How a cs2-dumper value maps onto what `Offsets` stores.
+#[derive(Debug, Clone, Copy)]
+enum EntryKind {
+    /// Module relative address of a global, stored as an absolute address.
+    Global,
+    /// Module relative address of a pointer, stored as the pointer plus an offset.
+    Pointer(u64),
+    /// Plain field offset, stored as is.
+    Field,
 }
 
-pub fn update_offsets_from_dumper(offsets: &mut Offsets) -> bool {
-    let urls = [
-        "https://raw.githubusercontent.com/sezzyaep/CS2-OFFSETS/main/offsets.json",
-        "https://raw.githubusercontent.com/hxuanyu/cs2-dumper/main/output/linux/offsets.json",
-        "https://raw.githubusercontent.com/a2x/cs2-dumper/main/output/linux/offsets.json",
-    ];
-
-    let mut content = String::new();
-    let mut found = false;
-
-    // First check local file "offsets.json" in current directory
-    if let Ok(c) = std::fs::read_to_string("offsets.json") {
-        log::info!("found local offsets.json");
-        content = c;
-        found = true;
-    } else {
-        for url in urls {
-            log::info!("fetching offsets from {}", url);
-            match ureq::get(url).call() {
-                Ok(response) => {
-                    if let Ok(c) = response.into_body().read_to_string() {
-                        content = c;
-                        found = true;
-                        break;
-                    } else {
-                        log::warn!("failed to read response body from {}", url);
-                    }
-                },
-                Err(e) => {
-                    log::warn!("failed to fetch offsets from {}: {}", url, e);
-                }
-            }
+struct DumperEntry {
+    module: &'static str,
+    key: &'static str,
+    name: &'static str,
+    kind: EntryKind,
+    get: fn(&Offsets) -> Option<u64>,
+}
+
+#[rustfmt::skip]
+const OFFSET_ENTRIES: &[DumperEntry] = &[
+    DumperEntry { module: cs2::CLIENT_LIB, key: "dwLocalPlayerController", name: "direct.local_player", kind: EntryKind::Global, get: |o| Some(o.direct.local_player) },
+    DumperEntry { module: cs2::CLIENT_LIB, key: "dwEntityList", name: "interface.entity", kind: EntryKind::Pointer(0x10), get: |o| Some(o.interface.entity) },
+    DumperEntry { module: cs2::CLIENT_LIB, key: "dwGlobalVars", name: "direct.global_vars", kind: EntryKind::Global, get: |o| o.direct.global_vars },
+    DumperEntry { module: cs2::CLIENT_LIB, key: "dwGameRules", name: "direct.game_rules", kind: EntryKind::Global, get: |o| o.direct.game_rules },
+    DumperEntry { module: cs2::ENGINE_LIB, key: "dwNetworkGameClient", name: "direct.network_client", kind: EntryKind::Global, get: |o| Some(o.direct.network_client) },
+    DumperEntry { module: cs2::ENGINE_LIB, key: "dwNetworkGameClient_deltaTick", name: "network_client.delta_tick", kind: EntryKind::Field, get: |o| Some(o.network_client.delta_tick) },
+    DumperEntry { module: cs2::ENGINE_LIB, key: "dwNetworkGameClient_signOnState", name: "network_client.sign_on_state", kind: EntryKind::Field, get: |o| o.network_client.sign_on_state },
+];
+
+/// An offset where the dumper output and our own scan or schema read disagree.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub name: &'static str,
+    pub ours: Option<u64>,
+    pub dumper: u64,
+}
+
+impl Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.ours {
+            Some(ours) => write!(
+                f,
+                "{}: found 0x{:X}, dumper has 0x{:X}",
+                self.name, ours, self.dumper
+            ),
+            None => write!(f, "{}: not found, dumper has 0x{:X}", self.name, self.dumper),
         }
     }
+}
+
+/// Outcome of [`DumperData::cross_check`].
+#[derive(Debug, Default)]
+pub struct CrossCheck {
+    /// Scanned offsets both sides have.
+    pub offsets: usize,
+    /// Schema fields both sides have.
+    pub fields: usize,
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// cs2-dumper output: the offsets file and the client schema file.
+pub struct DumperData {
+    offsets: HashMap<String, HashMap<String, u64>>,
+    schema: Option<Schema>,
+}
+
+impl DumperData {
+    /// Loads `offsets.json` and `client_dll.json` from the configured sources.
+    /// Returns `None` if neither could be loaded.
+    pub fn load(sources: &OffsetSourcesConfig, game_build: Option<u32>) -> Option<Self> {
+        let loader = SourceLoader::new(sources, game_build);
+        let offsets = loader.load(SourceKind::Offsets, |content| {
+            serde_json::from_str(content)
+                .map_err(|e| log::warn!("failed to parse offsets.json: {e}"))
+                .ok()
+        });
+        let schema = loader.load(SourceKind::Schema, |content| {
+            Schema::from_json(content)
+                .map_err(|e| log::warn!("failed to parse client_dll.json: {e}"))
+                .ok()
+        });
+
+        if offsets.is_none() && schema.is_none() {
+            return None;
+        }
+        Some(Self {
+            offsets: offsets.unwrap_or_default(),
+            schema,
+        })
+    }
+
+    pub fn schema(&self) -> Option<&Schema> {
+        self.schema.as_ref()
+    }
+
+    fn module(&self, module: &str) -> Option<&HashMap<String, u64>> {
+        self.offsets.get(module).or_else(|| {
+            let (_, windows) = cs2::WINDOWS_MODULES.iter().find(|(linux, _)| *linux == module)?;
+            self.offsets.get(*windows)
+        })
+    }
 
-    if !found {
-        return false;
+    fn resolve(&self, entry: &DumperEntry, process: &Process, library: &LibraryOffsets) -> Option<u64> {
+        let value = *self.module(entry.module)?.get(entry.key)?;
+        let base = match entry.module {
+            cs2::CLIENT_LIB => library.client,
+            cs2::ENGINE_LIB => library.engine,
+            _ => return None,
+        };
+        Some(match entry.kind {
+            EntryKind::Global => base + value,
+            EntryKind::Pointer(offset) => process.read::<u64>(base + value) + offset,
+            EntryKind::Field => value,
+        })
     }
 
-    let dumper_output: DumperOutput = match serde_json::from_str(&content) {
-        Ok(o) => o,
-        Err(e) => {
-            log::warn!("failed to parse offsets.json: {}", e);
-            return false;
+    /// Returns the dumper value for the `Offsets` slot called `name`,
+    /// already converted to what that slot stores.
+    pub fn get(&self, name: &str, process: &Process, library: &LibraryOffsets) -> Option<u64> {
+        let entry = OFFSET_ENTRIES.iter().find(|e| e.name == name)?;
+        let value = self.resolve(entry, process, library);
+        if let Some(value) = value {
+            log::info!("using {} from dumper: 0x{:X}", entry.key, value);
         }
-    };
-
-    // Try to get values from linux keys first, then windows keys as fallback
-    let engine_map = dumper_output.libengine.or(dumper_output.engine);
-    
-    if let Some(map) = engine_map {
-        if let Some(val) = map.get("dwNetworkGameClient") {
-             offsets.direct.network_client = offsets.library.engine + *val;
-             log::info!("updated dwNetworkGameClient from dumper: 0x{:X}", val);
+        value
+    }
+
+    /// Compares every offset the dumper knows against what we found.
+    pub fn cross_check(&self, offsets: &Offsets, process: &Process) -> CrossCheck {
+        let mut check = CrossCheck::default();
+
+        for entry in OFFSET_ENTRIES {
+            let Some(dumper) = self.resolve(entry, process, &offsets.library) else {
+                continue;
+            };
+            check.offsets += 1;
+            let ours = (entry.get)(offsets);
+            if ours != Some(dumper) {
+                check.disagreements.push(Disagreement {
+                    name: entry.name,
+                    ours,
+                    dumper,
+                });
+            }
         }
-        
-        if let Some(val) = map.get("dwNetworkGameClient_deltaTick") {
-            offsets.network_client.delta_tick = *val;
-            log::info!("updated delta_tick from dumper: 0x{:X}", val);
-        } else if let Some(val) = map.get("dwNetworkGameClient_clientTickCount") {
-             log::info!("found clientTickCount: 0x{:X} (not using as delta_tick yet)", val);
+
+        if let Some(schema) = &self.schema {
+            let (fields, disagreements) = compare_schema(schema, offsets);
+            check.fields = fields;
+            check.disagreements.extend(disagreements);
         }
+
+        check
     }
+}
 
-    true
+/// Compares the schema fields `schema` has against `offsets`. Returns how
+/// many were compared and the ones that differ.
+fn compare_schema(schema: &Schema, offsets: &Offsets) -> (usize, Vec<Disagreement>) {
+    let mut theirs = Offsets::default();
+    let report = resolve_schema_offsets(schema, &mut theirs);
+    let mut compared = 0;
+    let mut disagreements = Vec::new();
+    for field in SCHEMA_FIELDS {
+        if report.missing.iter().any(|m| m.name == field.name) {
+            continue;
+        }
+        let Some(dumper) = field.get(&theirs) else {
+            continue;
+        };
+        compared += 1;
+        let ours = field.get(offsets);
+        if ours != Some(dumper) {
+            disagreements.push(Disagreement {
+                name: field.name,
+                ours,
+                dumper,
+            });
+        }
+    }
+    (compared, disagreements)
+}
+
+/// Our offsets as cs2-dumper `(module, key, value)` entries, relative to their
+/// module. Pointers read at runtime cannot be converted back and are left out.
+pub fn dumper_entries(offsets: &Offsets) -> Vec<(&'static str, &'static str, u64)> {
+    OFFSET_ENTRIES
+        .iter()
+        .filter_map(|entry| {
+            let base = match entry.module {
+                cs2::CLIENT_LIB => offsets.library.client,
+                cs2::ENGINE_LIB => offsets.library.engine,
+                _ => return None,
+            };
+            let value = (entry.get)(offsets)?;
+            let value = match entry.kind {
+                EntryKind::Global => value.checked_sub(base)?,
+                EntryKind::Pointer(_) => return None,
+                EntryKind::Field => value,
+            };
+            Some((entry.module, entry.key, value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_DLL: &str = include_str!("testdata/client_dll.json");
+
+    #[test]
+    fn compares_windows_keyed_schema() {
+        let schema = Schema::from_json(CLIENT_DLL).unwrap();
+        let mut offsets = Offsets::default();
+        resolve_schema_offsets(&schema, &mut offsets);
+
+        let (compared, disagreements) = compare_schema(&schema, &offsets);
+        assert!(compared > 0);
+        assert!(disagreements.is_empty());
+
+        offsets.weapon.item = 0x58;
+        let (_, disagreements) = compare_schema(&schema, &offsets);
+        assert_eq!(disagreements.len(), 1);
+        assert_eq!(disagreements[0].name, "weapon.item");
+        assert_eq!(disagreements[0].dumper, 80);
+    }
+
+    #[test]
+    fn unknown_module_compares_nothing() {
+        let schema = Schema::from_json(&CLIENT_DLL.replace("client.dll", "server.dll")).unwrap();
+        let (compared, disagreements) = compare_schema(&schema, &Offsets::default());
+        assert_eq!(compared, 0);
+        assert!(disagreements.is_empty());
+    }
 }
@@ -2,7 +2,7 @@ use crate::cs2::CS2;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Player {
-    controller: u64,
+    pub(crate) controller: u64,
     pub(crate) pawn: u64,
 }
 
@@ -37,7 +37,7 @@ impl Player {
         Some(entity)
     }
 
-    fn get_entity(cs2: &CS2, handle: i32) -> Option<u64> {
+    pub fn get_entity(cs2: &CS2, handle: i32) -> Option<u64> {
         let index = handle as u64 & 0x7FFF;
         let bucket_index = index >> 9;
         let index_in_bucket = index & 0x1FF;
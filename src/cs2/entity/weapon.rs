@@ -1,9 +1,9 @@
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
-use strum::{AsRefStr, EnumIter};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
-use crate::cs2::CS2;
+use crate::{cs2::CS2, game_data};
 
 #[derive(
     Debug, Default, Clone, PartialEq, Eq, Hash, AsRefStr, EnumIter, Serialize, Deserialize,
@@ -14,8 +14,35 @@ pub enum Weapon {
     #[default]
     Unknown,
 
+    /// Any knife without an entry of its own.
     Knife,
 
+    // Knives
+    KnifeCt,
+    KnifeT,
+    KnifeGg,
+    KnifeGhost,
+    Bayonet,
+    ClassicKnife,
+    FlipKnife,
+    GutKnife,
+    Karambit,
+    M9Bayonet,
+    HuntsmanKnife,
+    FalchionKnife,
+    BowieKnife,
+    ButterflyKnife,
+    ShadowDaggers,
+    ParacordKnife,
+    SurvivalKnife,
+    UrsusKnife,
+    NavajaKnife,
+    NomadKnife,
+    StilettoKnife,
+    TalonKnife,
+    SkeletonKnife,
+    KukriKnife,
+
     // Pistols
     Cz75A,
     Deagle,
@@ -52,12 +79,15 @@ pub enum Weapon {
     Aug,
     Famas,
     Galilar,
+    #[serde(rename = "m4a4", alias = "m4_a4")]
     M4A4,
+    #[serde(rename = "m4a1", alias = "m4_a1")]
     M4A1,
     Sg556,
 
     // Snipers
     Awp,
+    #[serde(rename = "g3sg1", alias = "g3_s_g1")]
     G3SG1,
     Scar20,
     Ssg08,
@@ -88,9 +118,117 @@ impl Weapon {
                 + cs2.offsets.weapon.item
                 + cs2.offsets.weapon.item_definition_index,
         );
-        Self::from_index(weapon_index)
+        match game_data::current().items() {
+            Some(items) => items.weapon(weapon_index as u32),
+            None => Self::from_index(weapon_index),
+        }
+    }
+
+    /// Item definition name in `items_game.txt`.
+    pub fn item_name(&self) -> Option<&'static str> {
+        use Weapon::*;
+        let name = match self {
+            Unknown | Knife => return None,
+            KnifeCt => "weapon_knife",
+            KnifeT => "weapon_knife_t",
+            KnifeGg => "weapon_knifegg",
+            KnifeGhost => "weapon_knife_ghost",
+            Bayonet => "weapon_bayonet",
+            ClassicKnife => "weapon_knife_css",
+            FlipKnife => "weapon_knife_flip",
+            GutKnife => "weapon_knife_gut",
+            Karambit => "weapon_knife_karambit",
+            M9Bayonet => "weapon_knife_m9_bayonet",
+            HuntsmanKnife => "weapon_knife_tactical",
+            FalchionKnife => "weapon_knife_falchion",
+            BowieKnife => "weapon_knife_survival_bowie",
+            ButterflyKnife => "weapon_knife_butterfly",
+            ShadowDaggers => "weapon_knife_push",
+            ParacordKnife => "weapon_knife_cord",
+            SurvivalKnife => "weapon_knife_canis",
+            UrsusKnife => "weapon_knife_ursus",
+            NavajaKnife => "weapon_knife_gypsy_jackknife",
+            NomadKnife => "weapon_knife_outdoor",
+            StilettoKnife => "weapon_knife_stiletto",
+            TalonKnife => "weapon_knife_widowmaker",
+            SkeletonKnife => "weapon_knife_skeleton",
+            KukriKnife => "weapon_knife_kukri",
+            Cz75A => "weapon_cz75a",
+            Deagle => "weapon_deagle",
+            DualBerettas => "weapon_elite",
+            FiveSeven => "weapon_fiveseven",
+            Glock => "weapon_glock",
+            P2000 => "weapon_hkp2000",
+            P250 => "weapon_p250",
+            Revolver => "weapon_revolver",
+            Tec9 => "weapon_tec9",
+            Usp => "weapon_usp_silencer",
+            Bizon => "weapon_bizon",
+            Mac10 => "weapon_mac10",
+            Mp5Sd => "weapon_mp5sd",
+            Mp7 => "weapon_mp7",
+            Mp9 => "weapon_mp9",
+            P90 => "weapon_p90",
+            Ump45 => "weapon_ump45",
+            M249 => "weapon_m249",
+            Negev => "weapon_negev",
+            Mag7 => "weapon_mag7",
+            Nova => "weapon_nova",
+            Sawedoff => "weapon_sawedoff",
+            Xm1014 => "weapon_xm1014",
+            Ak47 => "weapon_ak47",
+            Aug => "weapon_aug",
+            Famas => "weapon_famas",
+            Galilar => "weapon_galilar",
+            M4A4 => "weapon_m4a1",
+            M4A1 => "weapon_m4a1_silencer",
+            Sg556 => "weapon_sg556",
+            Awp => "weapon_awp",
+            G3SG1 => "weapon_g3sg1",
+            Scar20 => "weapon_scar20",
+            Ssg08 => "weapon_ssg08",
+            Taser => "weapon_taser",
+            Flashbang => "weapon_flashbang",
+            HeGrenade => "weapon_hegrenade",
+            Smoke => "weapon_smokegrenade",
+            Molotov => "weapon_molotov",
+            Decoy => "weapon_decoy",
+            Incendiary => "weapon_incgrenade",
+            C4 => "weapon_c4",
+        };
+        Some(name)
+    }
+
+    /// Weapon for an item definition name. Knife models this build does not
+    /// know are [`Weapon::Knife`].
+    pub fn from_item_name(name: &str) -> Self {
+        match Self::iter().find(|weapon| weapon.item_name() == Some(name)) {
+            Some(weapon) => weapon,
+            None if name.starts_with("weapon_knife") => Self::Knife,
+            None => Self::Unknown,
+        }
+    }
+
+    pub fn is_knife(&self) -> bool {
+        self.fallback().is_some() || *self == Weapon::Knife
+    }
+
+    /// Entry used when the config has none for this weapon: the any-knife
+    /// entry for every knife model.
+    pub fn fallback(&self) -> Option<Weapon> {
+        use Weapon::*;
+        match self {
+            KnifeCt | KnifeT | KnifeGg | KnifeGhost | Bayonet | ClassicKnife | FlipKnife
+            | GutKnife | Karambit | M9Bayonet | HuntsmanKnife | FalchionKnife | BowieKnife
+            | ButterflyKnife | ShadowDaggers | ParacordKnife | SurvivalKnife | UrsusKnife
+            | NavajaKnife | NomadKnife | StilettoKnife | TalonKnife | SkeletonKnife
+            | KukriKnife => Some(Knife),
+            _ => None,
+        }
     }
 
+    /// Built-in table, used when no item database is loaded. Knife indices
+    /// this build does not know are [`Weapon::Knife`].
     pub fn from_index(index: u16) -> Self {
         use Weapon::*;
         match index {
@@ -125,8 +263,6 @@ impl Weapon {
             38 => Scar20,
             39 => Sg556,
             40 => Ssg08,
-            41 => Knife,
-            42 => Knife,
             43 => Flashbang,
             44 => HeGrenade,
             45 => Smoke,
@@ -134,36 +270,68 @@ impl Weapon {
             47 => Decoy,
             48 => Incendiary,
             49 => C4,
-            59 => Knife,
             60 => M4A1,
             61 => Usp,
             63 => Cz75A,
             64 => Revolver,
-            80 => Knife,
-            500 => Knife,
-            505 => Knife,
-            506 => Knife,
-            507 => Knife,
-            508 => Knife,
-            509 => Knife,
-            512 => Knife,
-            514 => Knife,
-            515 => Knife,
-            516 => Knife,
-            519 => Knife,
-            520 => Knife,
-            522 => Knife,
-            523 => Knife,
+            41 => KnifeGg,
+            42 => KnifeCt,
+            59 => KnifeT,
+            80 => KnifeGhost,
+            500 => Bayonet,
+            503 => ClassicKnife,
+            505 => FlipKnife,
+            506 => GutKnife,
+            507 => Karambit,
+            508 => M9Bayonet,
+            509 => HuntsmanKnife,
+            512 => FalchionKnife,
+            514 => BowieKnife,
+            515 => ButterflyKnife,
+            516 => ShadowDaggers,
+            517 => ParacordKnife,
+            518 => SurvivalKnife,
+            519 => UrsusKnife,
+            520 => NavajaKnife,
+            521 => NomadKnife,
+            522 => StilettoKnife,
+            523 => TalonKnife,
+            525 => SkeletonKnife,
+            526 => KukriKnife,
+            _ if (500..600).contains(&index) => Knife,
             _ => Unknown,
         }
     }
-}
 
-impl Display for Weapon {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
+    /// Built-in English name, used without localization files.
+    pub fn english_name(&self) -> &'static str {
+        match self {
             Weapon::Unknown => "Unknown",
             Weapon::Knife => "Knife",
+            Weapon::KnifeCt => "Default CT Knife",
+            Weapon::KnifeT => "Default T Knife",
+            Weapon::KnifeGg => "Golden Knife",
+            Weapon::KnifeGhost => "Spectral Shiv",
+            Weapon::Bayonet => "Bayonet",
+            Weapon::ClassicKnife => "Classic Knife",
+            Weapon::FlipKnife => "Flip Knife",
+            Weapon::GutKnife => "Gut Knife",
+            Weapon::Karambit => "Karambit",
+            Weapon::M9Bayonet => "M9 Bayonet",
+            Weapon::HuntsmanKnife => "Huntsman Knife",
+            Weapon::FalchionKnife => "Falchion Knife",
+            Weapon::BowieKnife => "Bowie Knife",
+            Weapon::ButterflyKnife => "Butterfly Knife",
+            Weapon::ShadowDaggers => "Shadow Daggers",
+            Weapon::ParacordKnife => "Paracord Knife",
+            Weapon::SurvivalKnife => "Survival Knife",
+            Weapon::UrsusKnife => "Ursus Knife",
+            Weapon::NavajaKnife => "Navaja Knife",
+            Weapon::NomadKnife => "Nomad Knife",
+            Weapon::StilettoKnife => "Stiletto Knife",
+            Weapon::TalonKnife => "Talon Knife",
+            Weapon::SkeletonKnife => "Skeleton Knife",
+            Weapon::KukriKnife => "Kukri Knife",
             Weapon::Cz75A => "CZ75-Auto",
             Weapon::Deagle => "Desert Eagle",
             Weapon::DualBerettas => "Dual Berettas",
@@ -206,7 +374,38 @@ impl Display for Weapon {
             Weapon::Decoy => "Decoy Grenade",
             Weapon::Incendiary => "Incendiary Grenade",
             Weapon::C4 => "C4 Explosive",
-        };
-        write!(f, "{}", s)
+        }
+    }
+}
+
+impl Display for Weapon {
+    /// Localized name from the game files, or the built-in English name.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let game_data = game_data::current();
+        let name = game_data.weapon_name(self).unwrap_or(self.english_name());
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_knives_are_knives() {
+        assert_eq!(Weapon::from_index(507), Weapon::Karambit);
+        assert_eq!(Weapon::from_index(59), Weapon::KnifeT);
+        assert_eq!(Weapon::from_index(524), Weapon::Knife);
+        assert_eq!(Weapon::from_index(599), Weapon::Knife);
+        assert_eq!(Weapon::from_index(600), Weapon::Unknown);
+        assert_eq!(Weapon::from_index(12), Weapon::Unknown);
+
+        assert_eq!(
+            Weapon::from_item_name("weapon_knife_karambit"),
+            Weapon::Karambit
+        );
+        assert_eq!(Weapon::from_item_name("weapon_knife_new"), Weapon::Knife);
+        assert_eq!(Weapon::from_item_name("weapon_new"), Weapon::Unknown);
+        assert!(Weapon::from_index(524).is_knife());
     }
 }
@@ -0,0 +1,223 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde_json::json;
+
+use crate::{
+    constants::cs2,
+    cs2::{dumper::dumper_entries, find_offsets::SCHEMA_FIELDS, offsets::Offsets},
+};
+
+/// Resolved offsets in the shape cs2-dumper writes them: module globals
+/// relative to their module, and schema fields grouped by class.
+pub struct OffsetExport {
+    build_id: String,
+    globals: BTreeMap<&'static str, BTreeMap<&'static str, u64>>,
+    classes: BTreeMap<&'static str, BTreeMap<&'static str, u64>>,
+}
+
+impl OffsetExport {
+    pub fn new(offsets: &Offsets, build_id: String) -> Self {
+        let mut globals: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for (module, key, value) in dumper_entries(offsets) {
+            globals.entry(module).or_default().insert(key, value);
+        }
+
+        let mut classes: BTreeMap<_, BTreeMap<_, _>> = BTreeMap::new();
+        for field in SCHEMA_FIELDS {
+            if let Some(offset) = field.get(offsets) {
+                classes.entry(field.class).or_default().insert(field.field, offset);
+            }
+        }
+
+        Self {
+            build_id,
+            globals,
+            classes,
+        }
+    }
+
+    /// cs2-dumper `offsets.json`
+    pub fn offsets_json(&self) -> String {
+        serde_json::to_string_pretty(&self.globals).unwrap_or_default()
+    }
+
+    /// cs2-dumper `client_dll.json`
+    pub fn schema_json(&self) -> String {
+        let classes: BTreeMap<_, _> = self
+            .classes
+            .iter()
+            .map(|(class, fields)| (*class, json!({ "fields": fields })))
+            .collect();
+        let out = json!({ cs2::CLIENT_LIB: { "classes": classes } });
+        serde_json::to_string_pretty(&out).unwrap_or_default()
+    }
+
+    pub fn rust_module(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "// libclient.so build id {}", self.build_id);
+        let _ = writeln!(out, "#![allow(non_upper_case_globals, non_snake_case)]");
+        let _ = writeln!(out, "\npub mod offsets {{");
+        for (module, values) in &self.globals {
+            let _ = writeln!(out, "    pub mod {} {{", identifier(module));
+            for (key, value) in values {
+                let _ = writeln!(out, "        pub const {key}: usize = 0x{value:X};");
+            }
+            let _ = writeln!(out, "    }}");
+        }
+        let _ = writeln!(out, "}}");
+        let _ = writeln!(out, "\npub mod schemas {{");
+        let _ = writeln!(out, "    pub mod {} {{", identifier(cs2::CLIENT_LIB));
+        for (class, fields) in &self.classes {
+            let _ = writeln!(out, "        pub mod {class} {{");
+            for (field, offset) in fields {
+                let _ = writeln!(out, "            pub const {field}: usize = 0x{offset:X};");
+            }
+            let _ = writeln!(out, "        }}");
+        }
+        let _ = writeln!(out, "    }}\n}}");
+        out
+    }
+
+    pub fn c_header(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "// libclient.so build id {}", self.build_id);
+        let _ = writeln!(out, "#pragma once\n\n#include <stddef.h>");
+        for (module, values) in &self.globals {
+            let _ = writeln!(out);
+            for (key, value) in values {
+                let name = format!("{}_{key}", identifier(module));
+                let _ = writeln!(out, "static const ptrdiff_t {name} = 0x{value:X};");
+            }
+        }
+        for (class, fields) in &self.classes {
+            let _ = writeln!(out);
+            for (field, offset) in fields {
+                let _ = writeln!(out, "static const ptrdiff_t {class}_{field} = 0x{offset:X};");
+            }
+        }
+        out
+    }
+
+    /// Writes every format into `<dir>/<build id>/` and returns that directory.
+    pub fn write(&self, dir: &Path) -> io::Result<PathBuf> {
+        let dir = dir.join(&self.build_id);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("offsets.json"), self.offsets_json())?;
+        std::fs::write(dir.join("client_dll.json"), self.schema_json())?;
+        std::fs::write(dir.join("offsets.rs"), self.rust_module())?;
+        std::fs::write(dir.join("offsets.h"), self.c_header())?;
+        Ok(dir)
+    }
+}
+
+/// `libclient.so` -> `libclient_so`
+fn identifier(module: &str) -> String {
+    module
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    const CLIENT: u64 = 0x7F00_0000_0000;
+    const ENGINE: u64 = 0x7E00_0000_0000;
+
+    fn export() -> OffsetExport {
+        let mut offsets = Offsets::default();
+        offsets.library.client = CLIENT;
+        offsets.library.engine = ENGINE;
+        offsets.direct.local_player = CLIENT + 0x1A5_2D20;
+        offsets.direct.game_rules = Some(CLIENT + 0x1A6_B5C8);
+        offsets.direct.network_client = ENGINE + 0x53_FCE0;
+        offsets.interface.entity = CLIENT + 0x1B0_0000;
+        offsets.network_client.delta_tick = 0x24C;
+        offsets.network_client.sign_on_state = Some(0x240);
+        offsets.pawn.weapon = 0x13A0;
+        offsets.skin.fallback_paint_kit = Some(0x1608);
+        OffsetExport::new(&offsets, "0123abcd".to_string())
+    }
+
+    #[test]
+    fn writes_dumper_json() {
+        let export = export();
+        let offsets: Value = serde_json::from_str(&export.offsets_json()).unwrap();
+        let client = &offsets[cs2::CLIENT_LIB];
+        assert_eq!(client["dwLocalPlayerController"], 0x1A5_2D20);
+        assert_eq!(client["dwGameRules"], 0x1A6_B5C8);
+        // pointers read at runtime and offsets not found are left out
+        assert!(client.get("dwEntityList").is_none());
+        assert!(client.get("dwGlobalVars").is_none());
+        let engine = &offsets[cs2::ENGINE_LIB];
+        assert_eq!(engine["dwNetworkGameClient"], 0x53_FCE0);
+        assert_eq!(engine["dwNetworkGameClient_deltaTick"], 0x24C);
+        assert_eq!(engine["dwNetworkGameClient_signOnState"], 0x240);
+
+        let schema: Value = serde_json::from_str(&export.schema_json()).unwrap();
+        let classes = &schema[cs2::CLIENT_LIB]["classes"];
+        assert_eq!(
+            classes["C_CSPlayerPawn"]["fields"]["m_pClippingWeapon"],
+            0x13A0
+        );
+        let econ_entity = &classes["C_EconEntity"]["fields"];
+        assert_eq!(econ_entity["m_nFallbackPaintKit"], 0x1608);
+        assert!(econ_entity.get("m_nFallbackSeed").is_none());
+        assert!(classes.get("C_CSGameRules").is_none());
+    }
+
+    /// Names declared after `keyword` on each line.
+    fn constants<'a>(source: &'a str, keyword: &str) -> Vec<&'a str> {
+        source
+            .lines()
+            .filter_map(|line| line.split_once(keyword))
+            .map(|(_, rest)| rest.split([':', ' ']).next().unwrap())
+            .collect()
+    }
+
+    fn is_identifier(name: &str) -> bool {
+        !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+
+    #[test]
+    fn writes_rust_module() {
+        let module = export().rust_module();
+        assert!(module.starts_with("// libclient.so build id 0123abcd\n"));
+        assert!(module.contains("    pub mod libclient_so {\n"));
+        assert!(module.contains("    pub mod libengine2_so {\n"));
+        assert!(module.contains("        pub const dwLocalPlayerController: usize = 0x1A52D20;\n"));
+        assert!(module.contains("        pub mod C_CSPlayerPawn {\n"));
+        assert!(module.contains("            pub const m_pClippingWeapon: usize = 0x13A0;\n"));
+        let names = constants(&module, "pub const ");
+        assert!(names.contains(&"dwNetworkGameClient_signOnState"));
+        assert!(names.iter().all(|name| is_identifier(name)), "{names:?}");
+        assert_eq!(module.matches('{').count(), module.matches('}').count());
+    }
+
+    #[test]
+    fn writes_c_header() {
+        let header = export().c_header();
+        assert!(header.starts_with("// libclient.so build id 0123abcd\n#pragma once\n"));
+        assert!(header.contains(
+            "static const ptrdiff_t libclient_so_dwLocalPlayerController = 0x1A52D20;\n"
+        ));
+        assert!(header.contains(
+            "static const ptrdiff_t libengine2_so_dwNetworkGameClient_deltaTick = 0x24C;\n"
+        ));
+        assert!(
+            header.contains("static const ptrdiff_t C_EconEntity_m_nFallbackPaintKit = 0x1608;\n")
+        );
+        let names = constants(&header, "static const ptrdiff_t ");
+        assert!(names.contains(&"C_CSPlayerPawn_m_pClippingWeapon"));
+        assert!(names.iter().all(|name| is_identifier(name)), "{names:?}");
+    }
+}
@@ -1,12 +1,280 @@
-use std::time::Instant;
+use std::{fmt::Display, time::Instant};
 
 use crate::{
+    config::{BASE_PATH, OffsetSourcesConfig},
     constants::cs2,
-    cs2::{CS2, offsets::Offsets, schema::Schema},
+    cs2::{
+        CS2,
+        dumper::DumperData,
+        interfaces::InterfaceRegistry,
+        offsets::Offsets,
+        schema::{SCHEMA_SNAPSHOT_NAME, Schema},
+    },
+    game_data::install,
 };
 
+/// Schema type a field is expected to have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    UInt8,
+    Int32,
+    UInt16,
+    UInt32,
+    Float32,
+    Handle,
+    Pointer,
+    Vector,
+    CharArray,
+    Class(&'static str),
+}
+
+impl FieldType {
+    pub fn matches(self, type_name: &str) -> bool {
+        match self {
+            FieldType::Bool => type_name == "bool",
+            FieldType::UInt8 => type_name == "uint8",
+            FieldType::Int32 => type_name == "int32",
+            FieldType::UInt16 => type_name == "uint16",
+            FieldType::UInt32 => type_name == "uint32",
+            FieldType::Float32 => type_name == "float32",
+            FieldType::Handle => type_name.starts_with("CHandle<"),
+            FieldType::Pointer => type_name.ends_with('*'),
+            FieldType::Vector => type_name.contains("UtlVector"),
+            FieldType::CharArray => type_name.starts_with("char["),
+            FieldType::Class(name) => type_name == name,
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldType::Bool => write!(f, "bool"),
+            FieldType::UInt8 => write!(f, "uint8"),
+            FieldType::Int32 => write!(f, "int32"),
+            FieldType::UInt16 => write!(f, "uint16"),
+            FieldType::UInt32 => write!(f, "uint32"),
+            FieldType::Float32 => write!(f, "float32"),
+            FieldType::Handle => write!(f, "CHandle<..>"),
+            FieldType::Pointer => write!(f, "pointer"),
+            FieldType::Vector => write!(f, "UtlVector<..>"),
+            FieldType::CharArray => write!(f, "char[..]"),
+            FieldType::Class(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// A `libclient.so` schema field read by [`CS2::find_offsets`].
+///
+/// Required fields are stored as plain offsets, optional fields stay `None`
+/// in [`Offsets`] when the schema does not have them.
+pub struct SchemaField {
+    pub name: &'static str,
+    pub class: &'static str,
+    pub field: &'static str,
+    pub ty: FieldType,
+    pub required: bool,
+    get: fn(&Offsets) -> Option<u64>,
+    set: fn(&mut Offsets, u64),
+}
+
+impl SchemaField {
+    /// The value currently stored in this field's slot.
+    pub fn get(&self, offsets: &Offsets) -> Option<u64> {
+        (self.get)(offsets)
+    }
+
+    pub fn set(&self, offsets: &mut Offsets, offset: u64) {
+        (self.set)(offsets, offset)
+    }
+}
+
+macro_rules! required {
+    ($name:literal, $class:literal, $field:literal, $ty:expr, $($slot:ident).+) => {
+        SchemaField {
+            name: $name,
+            class: $class,
+            field: $field,
+            ty: $ty,
+            required: true,
+            get: |o| Some(o.$($slot).+),
+            set: |o, offset| o.$($slot).+ = offset,
+        }
+    };
+}
+
+macro_rules! optional {
+    ($name:literal, $class:literal, $field:literal, $ty:expr, $($slot:ident).+) => {
+        SchemaField {
+            name: $name,
+            class: $class,
+            field: $field,
+            ty: $ty,
+            required: false,
+            get: |o| o.$($slot).+,
+            set: |o, offset| o.$($slot).+ = Some(offset),
+        }
+    };
+}
+
+/// Every `libclient.so` field `find_offsets` depends on.
+#[rustfmt::skip]
+pub const SCHEMA_FIELDS: &[SchemaField] = &[
+    required!("controller.pawn", "CBasePlayerController", "m_hPawn", FieldType::Handle, controller.pawn),
+    optional!("controller.team", "C_BaseEntity", "m_iTeamNum", FieldType::UInt8, controller.team),
+    required!("pawn.weapon", "C_CSPlayerPawn", "m_pClippingWeapon", FieldType::Pointer, pawn.weapon),
+    required!("pawn.weapon_services", "C_BasePlayerPawn", "m_pWeaponServices", FieldType::Pointer, pawn.weapon_services),
+    optional!("pawn.controller", "C_BasePlayerPawn", "m_hController", FieldType::Handle, pawn.controller),
+    required!("weapon_services.weapons", "CPlayer_WeaponServices", "m_hMyWeapons", FieldType::Vector, weapon_services.weapons),
+    required!("weapon.attribute_manager", "C_EconEntity", "m_AttributeManager", FieldType::Class("C_AttributeContainer"), weapon.attribute_manager),
+    required!("weapon.item", "C_AttributeContainer", "m_Item", FieldType::Class("C_EconItemView"), weapon.item),
+    required!("weapon.item_definition_index", "C_EconItemView", "m_iItemDefinitionIndex", FieldType::UInt16, weapon.item_definition_index),
+    optional!("skin.item_id_high", "C_EconItemView", "m_iItemIDHigh", FieldType::UInt32, skin.item_id_high),
+    optional!("skin.item_id_low", "C_EconItemView", "m_iItemIDLow", FieldType::UInt32, skin.item_id_low),
+    optional!("skin.account_id", "C_EconItemView", "m_iAccountID", FieldType::UInt32, skin.account_id),
+    optional!("skin.entity_quality", "C_EconItemView", "m_iEntityQuality", FieldType::Int32, skin.entity_quality),
+    optional!("skin.initialized", "C_EconItemView", "m_bInitialized", FieldType::Bool, skin.initialized),
+    optional!("skin.attribute_list", "C_EconItemView", "m_AttributeList", FieldType::Class("CAttributeList"), skin.attribute_list),
+    optional!("skin.networked_dynamic_attrs", "C_EconItemView", "m_NetworkedDynamicAttributes", FieldType::Class("CAttributeList"), skin.networked_dynamic_attrs),
+    optional!("skin.fallback_paint_kit", "C_EconEntity", "m_nFallbackPaintKit", FieldType::Int32, skin.fallback_paint_kit),
+    optional!("skin.fallback_seed", "C_EconEntity", "m_nFallbackSeed", FieldType::Int32, skin.fallback_seed),
+    optional!("skin.fallback_wear", "C_EconEntity", "m_flFallbackWear", FieldType::Float32, skin.fallback_wear),
+    optional!("skin.fallback_stattrak", "C_EconEntity", "m_nFallbackStatTrak", FieldType::Int32, skin.fallback_stattrak),
+    optional!("skin.custom_name", "C_EconItemView", "m_szCustomName", FieldType::CharArray, skin.custom_name),
+    optional!("skin.original_owner_xuid_low", "C_EconEntity", "m_OriginalOwnerXuidLow", FieldType::UInt32, skin.original_owner_xuid_low),
+    optional!("skin.original_owner_xuid_high", "C_EconEntity", "m_OriginalOwnerXuidHigh", FieldType::UInt32, skin.original_owner_xuid_high),
+    optional!("game_rules.warmup_period", "C_CSGameRules", "m_bWarmupPeriod", FieldType::Bool, game_rules.warmup_period),
+    optional!("game_rules.round_win_status", "C_CSGameRules", "m_iRoundWinStatus", FieldType::Int32, game_rules.round_win_status),
+];
+
+/// Classes whose size `find_offsets` reads.
+pub const SCHEMA_CLASS_SIZES: &[&str] = &["CEntityIdentity"];
+
+/// `CEntityIdentity` size, for schemas without class sizes.
+const ENTITY_IDENTITY_SIZE: i32 = 0x78;
+
+#[derive(Debug)]
+pub struct MissingOffset {
+    pub name: &'static str,
+    pub class: &'static str,
+    pub field: Option<&'static str>,
+    pub required: bool,
+}
+
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub name: &'static str,
+    pub class: &'static str,
+    pub field: &'static str,
+    pub expected: FieldType,
+    pub found: String,
+}
+
+/// Everything the schema resolver could not fill in, collected in one pass.
+#[derive(Debug, Default)]
+pub struct OffsetReport {
+    pub missing: Vec<MissingOffset>,
+    pub mismatched: Vec<TypeMismatch>,
+}
+
+impl OffsetReport {
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.mismatched.is_empty()
+    }
+
+    pub fn missing_required(&self) -> bool {
+        self.missing.iter().any(|m| m.required)
+    }
+}
+
+impl Display for OffsetReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} missing, {} with unexpected type",
+            self.missing.len(),
+            self.mismatched.len()
+        )?;
+        for missing in &self.missing {
+            let requirement = if missing.required { "required" } else { "optional" };
+            match missing.field {
+                Some(field) => write!(
+                    f,
+                    "\n  missing {requirement} {}: {}::{field}",
+                    missing.name, missing.class
+                )?,
+                None => write!(
+                    f,
+                    "\n  missing {requirement} {}: size of {}",
+                    missing.name, missing.class
+                )?,
+            }
+        }
+        for mismatch in &self.mismatched {
+            write!(
+                f,
+                "\n  {}: {}::{} is {}, expected {}",
+                mismatch.name, mismatch.class, mismatch.field, mismatch.found, mismatch.expected
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Fills every schema-derived slot of `offsets` from the `libclient.so` scope
+/// and reports everything that could not be resolved.
+pub fn resolve_schema_offsets(schema: &Schema, offsets: &mut Offsets) -> OffsetReport {
+    let mut report = OffsetReport::default();
+    let client = schema.get_library(cs2::CLIENT_LIB);
+
+    for entry in SCHEMA_FIELDS {
+        let field = client.and_then(|c| c.field(entry.class, entry.field));
+        let Some(field) = field else {
+            report.missing.push(MissingOffset {
+                name: entry.name,
+                class: entry.class,
+                field: Some(entry.field),
+                required: entry.required,
+            });
+            continue;
+        };
+
+        // snapshots without type information have an empty type name
+        if !field.type_name.is_empty() && !entry.ty.matches(&field.type_name) {
+            report.mismatched.push(TypeMismatch {
+                name: entry.name,
+                class: entry.class,
+                field: entry.field,
+                expected: entry.ty,
+                found: field.type_name.clone(),
+            });
+        }
+
+        entry.set(offsets, field.offset);
+    }
+
+    match client.and_then(|c| c.get_class("CEntityIdentity")) {
+        Some(class) if class.size() > 0 => offsets.entity_identity.size = class.size(),
+        _ => {
+            // cs2-dumper files have no sizes, the size has not changed in years
+            if !schema.has_class_sizes() {
+                offsets.entity_identity.size = ENTITY_IDENTITY_SIZE;
+            }
+            report.missing.push(MissingOffset {
+                name: "entity_identity.size",
+                class: "CEntityIdentity",
+                field: None,
+                required: schema.has_class_sizes(),
+            });
+        }
+    }
+
+    report
+}
+
 impl CS2 {
-    pub fn find_offsets(&self) -> Option<Offsets> {
+    pub fn find_offsets(&self, sources: &OffsetSourcesConfig) -> Option<Offsets> {
         let start = Instant::now();
         let mut offsets = Offsets::default();
 
@@ -17,44 +285,67 @@ impl CS2 {
         offsets.library.sdl = self.process.module_base_address(cs2::SDL_LIB)?;
         offsets.library.schema = self.process.module_base_address(cs2::SCHEMA_LIB)?;
 
-        let Some(resource_offset) = self
-            .process
-            .get_interface_offset(offsets.library.engine, "GameResourceServiceClientV0")
+        let interfaces = InterfaceRegistry::new(&self.process);
+
+        let Some(resource) = interfaces.latest(cs2::ENGINE_LIB, "GameResourceServiceClientV")
         else {
             log::warn!("could not get offset for GameResourceServiceClient");
             return None;
         };
-        offsets.interface.resource = resource_offset;
+        offsets.interface.resource = resource.instance;
 
         offsets.interface.entity =
             self.process.read::<u64>(offsets.interface.resource + 0x50) + 0x10;
 
-        let Some(cvar_address) = self
-            .process
-            .get_interface_offset(offsets.library.tier0, "VEngineCvar0")
-        else {
+        let Some(cvar) = interfaces.latest(cs2::TIER0_LIB, "VEngineCvar") else {
             log::warn!("could not get convar interface offset");
             return None;
         };
-        offsets.interface.cvar = cvar_address;
+        offsets.interface.cvar = cvar.instance;
 
-        let Some(input_address) = self
-            .process
-            .get_interface_offset(offsets.library.input, "InputSystemVersion0")
-        else {
+        let Some(input) = interfaces.latest(cs2::INPUT_LIB, "InputSystemVersion") else {
             log::warn!("could not get input interface offset");
             return None;
         };
-        offsets.interface.input = input_address;
+        offsets.interface.input = input.instance;
 
-        let Some(local_player) = self
+        // cs2-dumper output is a second opinion on everything we find ourselves,
+        // and a fallback where our own scans fail
+        let game_build = install::from_process(self.process.pid).and_then(|i| i.build_id);
+        if let Some(build) = game_build {
+            log::info!("game build {build}");
+        }
+        let dumper = DumperData::load(sources, game_build);
+
+        match self
             .process
             .scan("48 83 3D ? ? ? ? 00 0F 95 C0 C3", offsets.library.client)
-        else {
-            log::warn!("could not find local player offset");
-            return None;
+        {
+            Some(local_player) => {
+                offsets.direct.local_player =
+                    self.process.get_relative_address(local_player, 0x03, 0x08);
+            }
+            None => {
+                log::warn!("could not find local player offset");
+                offsets.direct.local_player = dumper
+                    .as_ref()
+                    .and_then(|d| d.get("direct.local_player", &self.process, &offsets.library))?;
+            }
+        }
+
+        // Global vars, optional: without them the main loop runs on a fixed interval
+        offsets.direct.global_vars = match self
+            .process
+            .scan("48 8D 05 ? ? ? ? 48 8B 00 8B 50 ? 85 D2", offsets.library.client)
+        {
+            Some(global_vars) => Some(self.process.get_relative_address(global_vars, 0x03, 0x07)),
+            None => {
+                log::warn!("could not find global vars offset");
+                dumper
+                    .as_ref()
+                    .and_then(|d| d.get("direct.global_vars", &self.process, &offsets.library))
+            }
         };
-        offsets.direct.local_player = self.process.get_relative_address(local_player, 0x03, 0x08);
 
         // Network Game Client
         let network_client_patterns = [
@@ -73,75 +364,66 @@ impl CS2 {
         }
 
         if let Some(network_client_addr) = network_client {
-             offsets.direct.network_client = self.process.get_relative_address(network_client_addr, 0x03, 0x07);
+            offsets.direct.network_client =
+                self.process.get_relative_address(network_client_addr, 0x03, 0x07);
         } else {
-             log::warn!("could not find network client offset via scan");
-             
-             // Try fetching from cs2-dumper
-             let dumper_success = crate::cs2::dumper::update_offsets_from_dumper(&mut offsets);
-             
-             if !dumper_success {
-                 log::warn!("using hardcoded fallback (0x8EB538)");
-                 offsets.direct.network_client = offsets.library.engine + 0x8EB538;
-             }
+            log::warn!("could not find network client offset via scan");
+            offsets.direct.network_client = match dumper
+                .as_ref()
+                .and_then(|d| d.get("direct.network_client", &self.process, &offsets.library))
+            {
+                Some(network_client) => network_client,
+                None => {
+                    log::warn!("using hardcoded fallback (0x8EB538)");
+                    offsets.library.engine + 0x8EB538
+                }
+            };
         }
 
         // Network client offsets
-        if offsets.network_client.delta_tick == 0 {
-            offsets.network_client.delta_tick = 0x158;
-        }
+        offsets.network_client.delta_tick = dumper
+            .as_ref()
+            .and_then(|d| d.get("network_client.delta_tick", &self.process, &offsets.library))
+            .unwrap_or(0x158);
 
-        let schema = Schema::new(&self.process, offsets.library.schema)?;
-        let client = schema.get_library(cs2::CLIENT_LIB)?;
-
-        offsets.controller.pawn = client.get("CBasePlayerController", "m_hPawn")?;
-
-        offsets.pawn.weapon = client.get("C_CSPlayerPawn", "m_pClippingWeapon")?;
-        offsets.pawn.weapon_services = client.get("C_BasePlayerPawn", "m_pWeaponServices")?;
-
-        offsets.weapon_services.weapons = client.get("CPlayer_WeaponServices", "m_hMyWeapons")?;
-
-        offsets.weapon.attribute_manager = client.get("C_EconEntity", "m_AttributeManager")?;
-        offsets.weapon.item = client.get("C_AttributeContainer", "m_Item")?;
-        offsets.weapon.item_definition_index =
-            client.get("C_EconItemView", "m_iItemDefinitionIndex")?;
-
-        offsets.entity_identity.size = client.get_class("CEntityIdentity")?.size();
-
-        // Skin changer offsets from CEconItemView
-        offsets.skin.item_id_high =
-            client.get("C_EconItemView", "m_iItemIDHigh").unwrap_or(0);
-        offsets.skin.item_id_low =
-            client.get("C_EconItemView", "m_iItemIDLow").unwrap_or(0);
-        offsets.skin.account_id =
-            client.get("C_EconItemView", "m_iAccountID").unwrap_or(0);
-        offsets.skin.entity_quality =
-            client.get("C_EconItemView", "m_iEntityQuality").unwrap_or(0);
-        offsets.skin.initialized =
-            client.get("C_EconItemView", "m_bInitialized").unwrap_or(0);
-        offsets.skin.attribute_list =
-            client.get("C_EconItemView", "m_AttributeList").unwrap_or(0);
-        offsets.skin.networked_dynamic_attrs =
-            client.get("C_EconItemView", "m_NetworkedDynamicAttributes").unwrap_or(0);
-        
-        // Fallback fields for client-side skin changing (C_EconEntity)
-        offsets.skin.fallback_paint_kit =
-            client.get("C_EconEntity", "m_nFallbackPaintKit").unwrap_or(0);
-        offsets.skin.fallback_seed =
-            client.get("C_EconEntity", "m_nFallbackSeed").unwrap_or(0);
-        offsets.skin.fallback_wear =
-            client.get("C_EconEntity", "m_flFallbackWear").unwrap_or(0);
-        offsets.skin.fallback_stattrak =
-            client.get("C_EconEntity", "m_nFallbackStatTrak").unwrap_or(0);
-        offsets.skin.custom_name =
-            client.get("C_EconItemView", "m_szCustomName").unwrap_or(0);
-        offsets.skin.original_owner_xuid_low =
-            client.get("C_EconEntity", "m_OriginalOwnerXuidLow").unwrap_or(0);
-        offsets.skin.original_owner_xuid_high =
-            client.get("C_EconEntity", "m_OriginalOwnerXuidHigh").unwrap_or(0);
+        // Game state inputs, optional: without them the game state is guessed
+        // from the global vars and the local player
+        offsets.network_client.sign_on_state = dumper
+            .as_ref()
+            .and_then(|d| d.get("network_client.sign_on_state", &self.process, &offsets.library));
+        offsets.direct.game_rules = dumper
+            .as_ref()
+            .and_then(|d| d.get("direct.game_rules", &self.process, &offsets.library));
+
+        let live_schema = Schema::new(&self.process, offsets.library.schema);
+        let snapshot = if live_schema.is_none() {
+            let path = BASE_PATH.join(SCHEMA_SNAPSHOT_NAME);
+            log::warn!("could not read schema system, trying snapshot {path:?}");
+            Schema::load(&path)
+                .map_err(|e| log::warn!("could not load schema snapshot: {e}"))
+                .ok()
+        } else {
+            None
+        };
+        let schema = live_schema
+            .as_ref()
+            .or(snapshot.as_ref())
+            .or_else(|| dumper.as_ref().and_then(|d| d.schema()));
+        let Some(schema) = schema else {
+            log::error!("no schema available");
+            return None;
+        };
+        let report = resolve_schema_offsets(schema, &mut offsets);
+        if report.missing_required() {
+            log::error!("schema offsets: {report}");
+            return None;
+        }
+        if !report.is_empty() {
+            log::warn!("schema offsets: {report}");
+        }
 
         log::info!(
-            "Skin offsets: item_id_high=0x{:X}, fallback_paint_kit=0x{:X}, fallback_seed=0x{:X}, fallback_wear=0x{:X}, fallback_stattrak=0x{:X}",
+            "Skin offsets: item_id_high={:X?}, fallback_paint_kit={:X?}, fallback_seed={:X?}, fallback_wear={:X?}, fallback_stattrak={:X?}",
             offsets.skin.item_id_high,
             offsets.skin.fallback_paint_kit,
             offsets.skin.fallback_seed,
@@ -149,7 +431,70 @@ impl CS2 {
             offsets.skin.fallback_stattrak
         );
 
+        if let Some(dumper) = &dumper {
+            let check = dumper.cross_check(&offsets, &self.process);
+            // nothing compared is not agreement, usually module names that did not match
+            if dumper.schema().is_some() && check.fields == 0 {
+                log::warn!("dumper schema has no libclient.so fields to compare");
+            }
+            if check.offsets + check.fields == 0 {
+                log::warn!("dumper output has nothing to compare against the found offsets");
+            } else if check.disagreements.is_empty() {
+                log::info!(
+                    "dumper output agrees with {} found offsets and {} schema fields",
+                    check.offsets,
+                    check.fields
+                );
+            }
+            for disagreement in check.disagreements {
+                log::warn!("dumper disagrees: {disagreement}");
+            }
+        }
+
         log::debug!("offsets: {:?} ({:?})", offsets, Instant::now() - start);
         Some(offsets)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_DLL: &str = include_str!("testdata/client_dll.json");
+
+    #[test]
+    fn resolves_offsets_from_dumper_schema() {
+        let schema = Schema::from_json(CLIENT_DLL).unwrap();
+        assert!(schema.get_library(cs2::CLIENT_LIB).is_some());
+        assert!(!schema.has_class_sizes());
+
+        let mut offsets = Offsets::default();
+        let report = resolve_schema_offsets(&schema, &mut offsets);
+        assert!(!report.missing_required(), "{report}");
+        assert!(report.mismatched.is_empty());
+
+        assert_eq!(offsets.controller.pawn, 1628);
+        assert_eq!(offsets.pawn.weapon_services, 4568);
+        assert_eq!(offsets.weapon.attribute_manager, 4808);
+        assert_eq!(offsets.weapon.item, 80);
+        assert_eq!(offsets.weapon.item_definition_index, 442);
+        assert_eq!(offsets.skin.fallback_paint_kit, Some(5704));
+        assert_eq!(offsets.skin.account_id, None);
+        assert_eq!(offsets.entity_identity.size, ENTITY_IDENTITY_SIZE);
+    }
+
+    #[test]
+    fn snapshot_without_identity_size_is_missing_required() {
+        let snapshot = r#"{ "scopes": { "libclient.so": { "name": "libclient.so", "classes": {} } } }"#;
+        let schema = Schema::from_json(snapshot).unwrap();
+        assert!(schema.has_class_sizes());
+
+        let report = resolve_schema_offsets(&schema, &mut Offsets::default());
+        assert!(
+            report
+                .missing
+                .iter()
+                .any(|m| m.name == "entity_identity.size" && m.required)
+        );
+    }
+}
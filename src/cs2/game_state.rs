@@ -0,0 +1,232 @@
+use std::fmt::Display;
+
+use crate::cs2::{CS2, entity::player::Player};
+
+// CNetworkGameClient sign-on states
+const SIGNON_NONE: i32 = 0;
+const SIGNON_CHALLENGE: i32 = 1;
+const SIGNON_CONNECTED: i32 = 2;
+const SIGNON_FULL: i32 = 6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameState {
+    #[default]
+    Menu,
+    Connecting,
+    Loading,
+    Warmup,
+    Live,
+    PostRound,
+}
+
+impl GameState {
+    /// On a map with a local player.
+    pub fn in_game(self) -> bool {
+        matches!(self, GameState::Warmup | GameState::Live | GameState::PostRound)
+    }
+}
+
+impl Display for GameState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            GameState::Menu => "menu",
+            GameState::Connecting => "connecting",
+            GameState::Loading => "loading",
+            GameState::Warmup => "warmup",
+            GameState::Live => "live",
+            GameState::PostRound => "post-round",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    StateChanged { old: GameState, new: GameState },
+    /// Entered a map, from the menu or a loading screen.
+    MapLoaded { map: String },
+    /// A round went live, after warmup or the previous round.
+    RoundStarted,
+}
+
+impl Display for GameEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameEvent::StateChanged { old, new } => write!(f, "game state {old} -> {new}"),
+            GameEvent::MapLoaded { map } => write!(f, "map loaded: {map}"),
+            GameEvent::RoundStarted => write!(f, "round started"),
+        }
+    }
+}
+
+/// Turns successive game states into transition events.
+#[derive(Debug, Default)]
+pub struct GameStateTracker {
+    state: GameState,
+}
+
+impl GameStateTracker {
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// `map_name` is only called when a map was entered.
+    pub fn update(&mut self, new: GameState, map_name: impl FnOnce() -> String) -> Vec<GameEvent> {
+        let old = self.state;
+        if old == new {
+            return Vec::new();
+        }
+        self.state = new;
+
+        let mut events = vec![GameEvent::StateChanged { old, new }];
+        if !old.in_game() && new.in_game() {
+            events.push(GameEvent::MapLoaded { map: map_name() });
+        }
+        if new == GameState::Live && matches!(old, GameState::Warmup | GameState::PostRound) {
+            events.push(GameEvent::RoundStarted);
+        }
+        events
+    }
+}
+
+impl CS2 {
+    /// Detects the current game state.
+    ///
+    /// The network client's sign-on state separates the menu, connecting and
+    /// loading. Once fully signed on, the game rules tell warmup, live and
+    /// post-round apart. Without the sign-on offset the global vars and the
+    /// local player are used instead, which cannot tell connecting from loading.
+    pub fn game_state(&self) -> GameState {
+        let network_client: u64 = self.process.read(self.offsets.direct.network_client);
+        let sign_on_state = match self.offsets.network_client.sign_on_state {
+            Some(offset) if network_client != 0 => {
+                Some(self.process.read::<i32>(network_client + offset))
+            }
+            Some(_) => Some(SIGNON_NONE),
+            None => None,
+        };
+
+        match sign_on_state {
+            Some(SIGNON_NONE) => return GameState::Menu,
+            Some(SIGNON_CHALLENGE | SIGNON_CONNECTED) => return GameState::Connecting,
+            Some(state) if state != SIGNON_FULL => return GameState::Loading,
+            Some(_) => {}
+            None => {
+                if self.offsets.direct.global_vars.is_some() && self.global_vars_address().is_none() {
+                    return GameState::Menu;
+                }
+            }
+        }
+
+        if Player::local_player(self).is_none() {
+            return if sign_on_state.is_some() {
+                GameState::Loading
+            } else {
+                GameState::Menu
+            };
+        }
+
+        let Some(game_rules) = self.offsets.direct.game_rules else {
+            return GameState::Live;
+        };
+        let game_rules: u64 = self.process.read(game_rules);
+        if game_rules == 0 {
+            return GameState::Live;
+        }
+        let offsets = &self.offsets.game_rules;
+        if let Some(warmup_period) = offsets.warmup_period
+            && self.process.read::<u8>(game_rules + warmup_period) != 0
+        {
+            return GameState::Warmup;
+        }
+        if let Some(round_win_status) = offsets.round_win_status
+            && self.process.read::<i32>(game_rules + round_win_status) != 0
+        {
+            return GameState::PostRound;
+        }
+        GameState::Live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use GameEvent::*;
+    use GameState::*;
+
+    /// Feeds `new` to the tracker, `map_name` panics unless a map was entered.
+    fn update(tracker: &mut GameStateTracker, new: GameState, map: Option<&str>) -> Vec<GameEvent> {
+        tracker.update(new, || map.expect("map name read without entering a map").to_string())
+    }
+
+    fn changed(old: GameState, new: GameState) -> GameEvent {
+        StateChanged { old, new }
+    }
+
+    #[test]
+    fn menu_to_live() {
+        let mut tracker = GameStateTracker::default();
+        assert_eq!(tracker.state(), Menu);
+        assert_eq!(update(&mut tracker, Menu, None), []);
+        assert_eq!(
+            update(&mut tracker, Loading, None),
+            [changed(Menu, Loading)]
+        );
+        assert_eq!(
+            update(&mut tracker, Warmup, Some("de_mirage")),
+            [
+                changed(Loading, Warmup),
+                MapLoaded {
+                    map: "de_mirage".to_string()
+                }
+            ]
+        );
+        assert_eq!(
+            update(&mut tracker, Live, None),
+            [changed(Warmup, Live), RoundStarted]
+        );
+        assert_eq!(tracker.state(), Live);
+    }
+
+    #[test]
+    fn rounds_restart_after_post_round() {
+        let mut tracker = GameStateTracker::default();
+        update(&mut tracker, Live, Some("de_mirage"));
+        assert_eq!(
+            update(&mut tracker, PostRound, None),
+            [changed(Live, PostRound)]
+        );
+        assert_eq!(
+            update(&mut tracker, Live, None),
+            [changed(PostRound, Live), RoundStarted]
+        );
+    }
+
+    #[test]
+    fn map_changes_load_the_new_map() {
+        let mut tracker = GameStateTracker::default();
+        // joining a match in progress is not a round start
+        assert_eq!(
+            update(&mut tracker, Live, Some("de_mirage")),
+            [
+                changed(Menu, Live),
+                MapLoaded {
+                    map: "de_mirage".to_string()
+                }
+            ]
+        );
+        assert_eq!(
+            update(&mut tracker, Loading, None),
+            [changed(Live, Loading)]
+        );
+        assert_eq!(
+            update(&mut tracker, Live, Some("de_inferno")),
+            [
+                changed(Loading, Live),
+                MapLoaded {
+                    map: "de_inferno".to_string()
+                }
+            ]
+        );
+    }
+}
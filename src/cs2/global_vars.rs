@@ -0,0 +1,59 @@
+use crate::cs2::CS2;
+
+/// Snapshot of the client's global vars.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GlobalVars {
+    pub frame_count: i32,
+    pub current_time: f32,
+    pub tick_count: i32,
+    pub map_name: String,
+}
+
+impl CS2 {
+    /// Address of the global vars, `None` if the offset was not found or the
+    /// game has not set them up yet (main menu).
+    pub(crate) fn global_vars_address(&self) -> Option<u64> {
+        let global_vars: u64 = self.process.read(self.offsets.direct.global_vars?);
+        (global_vars != 0).then_some(global_vars)
+    }
+
+    pub fn global_vars(&self) -> Option<GlobalVars> {
+        let address = self.global_vars_address()?;
+        let offsets = &self.offsets.global_vars;
+        let map_name: u64 = self.process.read(address + offsets.map_name);
+        Some(GlobalVars {
+            frame_count: self.process.read(address + offsets.frame_count),
+            current_time: self.process.read(address + offsets.current_time),
+            tick_count: self.process.read(address + offsets.tick_count),
+            map_name: if map_name == 0 {
+                String::new()
+            } else {
+                self.process.read_string_uncached(map_name)
+            },
+        })
+    }
+
+    /// Reads only the tick and frame count, cheap enough to poll every millisecond.
+    pub fn tick_state(&self) -> TickState {
+        if self.offsets.direct.global_vars.is_none() {
+            return TickState::Unknown;
+        }
+        let Some(address) = self.global_vars_address() else {
+            return TickState::Stopped;
+        };
+        let offsets = &self.offsets.global_vars;
+        TickState::Running {
+            tick: self.process.read(address + offsets.tick_count),
+            frame: self.process.read(address + offsets.frame_count),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickState {
+    /// Global vars offset not found, tick timing is unknown.
+    Unknown,
+    /// No global vars, the game is in the main menu or loading.
+    Stopped,
+    Running { tick: i32, frame: i32 },
+}
@@ -0,0 +1,146 @@
+use crate::{constants::cs2, os::process::Process};
+
+/// An interface registered through a module's `CreateInterface` list.
+#[derive(Debug, Clone)]
+pub struct Interface {
+    pub module: &'static str,
+    /// Registered name without the version suffix, e.g. `VEngineCvar`.
+    pub name: String,
+    /// Version suffix as registered, e.g. `007`. Empty if there is none.
+    pub version: String,
+    pub instance: u64,
+}
+
+impl Interface {
+    fn new(module: &'static str, full_name: &str, instance: u64) -> Self {
+        let name = full_name.trim_end_matches(|c: char| c.is_ascii_digit());
+        Self {
+            module,
+            name: name.to_string(),
+            version: full_name[name.len()..].to_string(),
+            instance,
+        }
+    }
+
+    pub fn full_name(&self) -> String {
+        format!("{}{}", self.name, self.version)
+    }
+
+    pub fn version_number(&self) -> Option<u32> {
+        self.version.parse().ok()
+    }
+}
+
+/// Every interface registered in the modules in [`cs2::LIBS`].
+#[derive(Debug, Default)]
+pub struct InterfaceRegistry {
+    interfaces: Vec<Interface>,
+}
+
+impl InterfaceRegistry {
+    pub fn new(process: &Process) -> Self {
+        let mut interfaces = Vec::new();
+        for module in cs2::LIBS {
+            let Some(base) = process.module_base_address(module) else {
+                continue;
+            };
+            for (full_name, instance) in process.list_interfaces(base) {
+                interfaces.push(Interface::new(module, &full_name, instance));
+            }
+        }
+        Self { interfaces }
+    }
+
+    pub fn all(&self) -> &[Interface] {
+        &self.interfaces
+    }
+
+    /// Looks up an interface by its full registered name, e.g. `VEngineCvar007`.
+    pub fn exact(&self, module: &str, full_name: &str) -> Option<&Interface> {
+        self.interfaces
+            .iter()
+            .find(|i| i.module == module && i.full_name() == full_name)
+    }
+
+    /// Looks up the highest registered version of an interface, e.g. `VEngineCvar`.
+    pub fn latest(&self, module: &str, name: &str) -> Option<&Interface> {
+        self.interfaces
+            .iter()
+            .filter(|i| i.module == module && i.name == name)
+            .max_by_key(|i| i.version_number())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_interfaces(interfaces: &[(&'static str, &str)]) -> InterfaceRegistry {
+        let interfaces = interfaces
+            .iter()
+            .enumerate()
+            .map(|(index, (module, full_name))| Interface::new(module, full_name, index as u64))
+            .collect();
+        InterfaceRegistry { interfaces }
+    }
+
+    #[test]
+    fn splits_the_version_suffix() {
+        let cvar = Interface::new(cs2::TIER0_LIB, "VEngineCvar007", 0);
+        assert_eq!(cvar.name, "VEngineCvar");
+        assert_eq!(cvar.version, "007");
+        assert_eq!(cvar.version_number(), Some(7));
+        assert_eq!(cvar.full_name(), "VEngineCvar007");
+
+        let unversioned = Interface::new(cs2::TIER0_LIB, "VEngineCvar", 0);
+        assert_eq!(unversioned.name, "VEngineCvar");
+        assert_eq!(unversioned.version, "");
+        assert_eq!(unversioned.version_number(), None);
+        assert_eq!(unversioned.full_name(), "VEngineCvar");
+    }
+
+    #[test]
+    fn finds_latest_and_exact_versions() {
+        let registry = with_interfaces(&[
+            (cs2::CLIENT_LIB, "Source2Client002"),
+            (cs2::CLIENT_LIB, "Source2Client010"),
+            (cs2::CLIENT_LIB, "Source2Client9"),
+            (cs2::CLIENT_LIB, "Source2Client"),
+            (cs2::ENGINE_LIB, "Source2Client099"),
+        ]);
+        let latest = registry.latest(cs2::CLIENT_LIB, "Source2Client").unwrap();
+        // versions compare as numbers, other modules are not searched
+        assert_eq!(latest.full_name(), "Source2Client010");
+        assert_eq!(latest.instance, 1);
+        assert!(
+            registry
+                .latest(cs2::CLIENT_LIB, "Source2Client01")
+                .is_none()
+        );
+
+        let exact = registry.exact(cs2::CLIENT_LIB, "Source2Client002").unwrap();
+        assert_eq!(exact.instance, 0);
+        let unversioned = registry.exact(cs2::CLIENT_LIB, "Source2Client").unwrap();
+        assert_eq!(unversioned.instance, 3);
+        assert!(registry.exact(cs2::CLIENT_LIB, "Source2Client2").is_none());
+        assert!(
+            registry
+                .exact(cs2::ENGINE_LIB, "Source2Client002")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn unversioned_names_order_below_numbered_ones() {
+        let registry = with_interfaces(&[
+            (cs2::TIER0_LIB, "VEngineCvar"),
+            (cs2::TIER0_LIB, "VEngineCvar001"),
+        ]);
+        let latest = registry.latest(cs2::TIER0_LIB, "VEngineCvar").unwrap();
+        assert_eq!(latest.version, "001");
+
+        let only = with_interfaces(&[(cs2::TIER0_LIB, "VEngineCvar")]);
+        let latest = only.latest(cs2::TIER0_LIB, "VEngineCvar").unwrap();
+        assert_eq!(latest.version_number(), None);
+    }
+}
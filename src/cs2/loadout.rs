@@ -0,0 +1,264 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{LoadoutRule, SkinChangerConfig, WeaponSkinConfig},
+    cs2::entity::weapon::Weapon,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Team {
+    T,
+    Ct,
+}
+
+impl Team {
+    /// From `m_iTeamNum`, spectators and unassigned have no team.
+    pub fn from_team_num(team_num: u8) -> Option<Self> {
+        match team_num {
+            2 => Some(Team::T),
+            3 => Some(Team::Ct),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    Casual,
+    Competitive,
+    Wingman,
+    ArmsRace,
+    Demolition,
+    Deathmatch,
+    Custom,
+}
+
+impl GameMode {
+    /// From the `game_type` and `game_mode` ConVars.
+    pub fn from_convars(game_type: i64, game_mode: i64) -> Option<Self> {
+        match (game_type, game_mode) {
+            (0, 0) => Some(GameMode::Casual),
+            (0, 1) => Some(GameMode::Competitive),
+            (0, 2) => Some(GameMode::Wingman),
+            (1, 0) => Some(GameMode::ArmsRace),
+            (1, 1) => Some(GameMode::Demolition),
+            (1, 2) => Some(GameMode::Deathmatch),
+            (3, _) => Some(GameMode::Custom),
+            _ => None,
+        }
+    }
+}
+
+/// What loadout rules are matched against.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchContext {
+    pub team: Option<Team>,
+    pub map: String,
+    pub mode: Option<GameMode>,
+    pub warmup: bool,
+}
+
+impl Display for MatchContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "team {:?}, map {:?}, mode {:?}, warmup {}",
+            self.team, self.map, self.mode, self.warmup
+        )
+    }
+}
+
+impl LoadoutRule {
+    /// Every condition the rule sets has to hold, unset conditions match anything.
+    pub fn matches(&self, context: &MatchContext) -> bool {
+        self.team.is_none_or(|team| context.team == Some(team))
+            && self
+                .map
+                .as_ref()
+                .is_none_or(|map| map.eq_ignore_ascii_case(&context.map))
+            && self.mode.is_none_or(|mode| context.mode == Some(mode))
+            && self.warmup.is_none_or(|warmup| context.warmup == warmup)
+    }
+}
+
+impl SkinChangerConfig {
+    /// Name of the loadout selected by the first matching rule. Rules naming
+    /// a loadout that does not exist are skipped.
+    pub fn active_loadout(&self, context: &MatchContext) -> Option<&str> {
+        self.rules
+            .iter()
+            .filter(|rule| self.loadouts.contains_key(&rule.loadout))
+            .find(|rule| rule.matches(context))
+            .map(|rule| rule.loadout.as_str())
+    }
+
+    /// Skin for `weapon`: the loadout's entry if it has one, the base `skins`
+    /// otherwise. Knives without an entry of their own use the `knife` entry
+    /// of the same place.
+    pub fn skin(&self, weapon: &Weapon, loadout: Option<&str>) -> Option<&WeaponSkinConfig> {
+        fn find<'a>(
+            skins: &'a HashMap<Weapon, WeaponSkinConfig>,
+            weapon: &Weapon,
+        ) -> Option<&'a WeaponSkinConfig> {
+            skins.get(weapon).or_else(|| skins.get(&weapon.fallback()?))
+        }
+
+        loadout
+            .and_then(|name| self.loadouts.get(name))
+            .and_then(|loadout| find(&loadout.skins, weapon))
+            .or_else(|| find(&self.skins, weapon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PaintKitRef;
+
+    fn config(source: &str) -> SkinChangerConfig {
+        toml::from_str(source).unwrap()
+    }
+
+    fn context(
+        team: Option<Team>,
+        map: &str,
+        mode: Option<GameMode>,
+        warmup: bool,
+    ) -> MatchContext {
+        MatchContext {
+            team,
+            map: map.to_string(),
+            mode,
+            warmup,
+        }
+    }
+
+    fn paint_kit(config: &SkinChangerConfig, weapon: Weapon, loadout: Option<&str>) -> Option<i32> {
+        config
+            .skin(&weapon, loadout)
+            .map(|skin| skin.paint_kit.id())
+    }
+
+    #[test]
+    fn rule_conditions() {
+        let rule = LoadoutRule {
+            team: Some(Team::Ct),
+            map: Some("de_mirage".to_string()),
+            mode: Some(GameMode::Competitive),
+            warmup: Some(false),
+            loadout: "ct".to_string(),
+        };
+        let live = context(
+            Some(Team::Ct),
+            "DE_Mirage",
+            Some(GameMode::Competitive),
+            false,
+        );
+        assert!(rule.matches(&live));
+        assert!(!rule.matches(&MatchContext {
+            team: Some(Team::T),
+            ..live.clone()
+        }));
+        assert!(!rule.matches(&MatchContext {
+            map: "de_inferno".to_string(),
+            ..live.clone()
+        }));
+        assert!(!rule.matches(&MatchContext {
+            mode: None,
+            ..live.clone()
+        }));
+        assert!(!rule.matches(&MatchContext {
+            warmup: true,
+            ..live.clone()
+        }));
+
+        let any = LoadoutRule {
+            loadout: "any".to_string(),
+            ..LoadoutRule::default()
+        };
+        assert!(any.matches(&MatchContext::default()));
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let config = config(
+            r#"
+            [loadouts.dm.skins.ak47]
+            paint_kit = 1
+            [loadouts.ct.skins.ak47]
+            paint_kit = 2
+
+            [[rules]]
+            mode = "deathmatch"
+            loadout = "dm"
+            [[rules]]
+            team = "ct"
+            loadout = "missing"
+            [[rules]]
+            team = "ct"
+            loadout = "ct"
+            "#,
+        );
+        let ct = context(Some(Team::Ct), "de_dust2", Some(GameMode::Casual), false);
+        assert_eq!(config.active_loadout(&ct), Some("ct"));
+        let dm = MatchContext {
+            mode: Some(GameMode::Deathmatch),
+            ..ct.clone()
+        };
+        assert_eq!(config.active_loadout(&dm), Some("dm"));
+        let t = MatchContext {
+            team: Some(Team::T),
+            ..ct
+        };
+        assert_eq!(config.active_loadout(&t), None);
+    }
+
+    #[test]
+    fn loadout_falls_back_to_base_skins() {
+        let config = config(
+            r#"
+            [skins.ak47]
+            paint_kit = 44
+            [skins.awp]
+            paint_kit = 344
+            [loadouts.ct.skins.awp]
+            paint_kit = 279
+            "#,
+        );
+        assert_eq!(paint_kit(&config, Weapon::Awp, Some("ct")), Some(279));
+        assert_eq!(paint_kit(&config, Weapon::Ak47, Some("ct")), Some(44));
+        assert_eq!(paint_kit(&config, Weapon::Awp, None), Some(344));
+        assert_eq!(paint_kit(&config, Weapon::Awp, Some("missing")), Some(344));
+        assert_eq!(paint_kit(&config, Weapon::Deagle, Some("ct")), None);
+    }
+
+    #[test]
+    fn knives_fall_back_to_knife_entry() {
+        let mut config = config(
+            r#"
+            [skins.knife]
+            paint_kit = 415
+            [skins.karambit]
+            paint_kit = 38
+            [loadouts.ct.skins.knife]
+            paint_kit = 416
+            "#,
+        );
+        assert_eq!(paint_kit(&config, Weapon::Karambit, None), Some(38));
+        assert_eq!(paint_kit(&config, Weapon::Bayonet, None), Some(415));
+        // a model this build does not know
+        assert_eq!(paint_kit(&config, Weapon::from_index(524), None), Some(415));
+        // the loadout's knife entry beats a model's base entry
+        assert_eq!(paint_kit(&config, Weapon::Karambit, Some("ct")), Some(416));
+        assert_eq!(paint_kit(&config, Weapon::Ak47, None), None);
+
+        config.skins.remove(&Weapon::Knife);
+        config.skins.get_mut(&Weapon::Karambit).unwrap().paint_kit = PaintKitRef::Id(0);
+        assert_eq!(paint_kit(&config, Weapon::Karambit, None), Some(0));
+        assert_eq!(paint_kit(&config, Weapon::Bayonet, None), None);
+    }
+}
@@ -1,14 +1,32 @@
+pub mod convars;
 pub mod entity;
 mod dumper;
-mod find_offsets;
-mod offsets;
-mod schema;
+pub mod export;
+pub mod game_state;
+pub mod global_vars;
+pub mod interfaces;
+pub mod loadout;
+pub mod find_offsets;
+mod offset_sources;
+pub mod offsets;
+pub mod schema;
+pub mod schema_diff;
+pub mod self_test;
 mod skin_changer;
+pub mod tick;
+
+use std::collections::HashSet;
 
 use crate::{
     config::SkinChangerConfig,
     constants::cs2,
-    cs2::offsets::Offsets,
+    cs2::{
+        entity::weapon::Weapon,
+        game_state::{GameEvent, GameStateTracker},
+        loadout::MatchContext,
+        offsets::Offsets,
+        skin_changer::SkinFeatures,
+    },
     os::process::Process,
 };
 
@@ -17,6 +35,13 @@ pub struct CS2 {
     is_valid: bool,
     pub(crate) process: Process,
     pub(crate) offsets: Offsets,
+    pub(crate) skin_features: SkinFeatures,
+    pub(crate) game: GameStateTracker,
+    /// Map and mode of the current map, team and warmup are filled in per run.
+    pub(crate) match_context: MatchContext,
+    pub(crate) active_loadout: Option<String>,
+    /// Weapons written on the next run even if their paint kit is already set.
+    pub(crate) reapply: HashSet<Weapon>,
 }
 
 impl CS2 {
@@ -25,6 +50,11 @@ impl CS2 {
             is_valid: false,
             process: Process::new(-1),
             offsets: Offsets::default(),
+            skin_features: SkinFeatures::default(),
+            game: GameStateTracker::default(),
+            match_context: MatchContext::default(),
+            active_loadout: None,
+            reapply: HashSet::new(),
         }
     }
 
@@ -32,7 +62,7 @@ impl CS2 {
         self.is_valid && self.process.is_valid()
     }
 
-    pub fn setup(&mut self) {
+    pub fn setup(&mut self, config: &SkinChangerConfig) {
         let Some(process) = Process::open(cs2::PROCESS_NAME) else {
             self.is_valid = false;
             return;
@@ -40,7 +70,7 @@ impl CS2 {
         log::info!("process found, pid: {}", process.pid);
         self.process = process;
 
-        self.offsets = match self.find_offsets() {
+        self.offsets = match self.find_offsets(&config.offset_sources) {
             Some(offsets) => offsets,
             None => {
                 self.process = Process::new(-1);
@@ -50,9 +80,26 @@ impl CS2 {
         };
         log::info!("offsets found");
 
+        for check in self.self_test() {
+            if check.failed() {
+                log::warn!("self-test: {check}");
+            } else {
+                log::debug!("self-test: {check}");
+            }
+        }
+
+        self.skin_features = SkinFeatures::new(&self.offsets.skin);
+        self.skin_features.log();
+        self.game = GameStateTracker::default();
+
         self.is_valid = true;
     }
 
+    /// Marks weapons whose config changed, so their skin is rewritten on the next run.
+    pub fn reapply(&mut self, weapons: HashSet<Weapon>) {
+        self.reapply.extend(weapons);
+    }
+
     pub fn run(&mut self, config: &SkinChangerConfig) {
         if !self.process.is_valid() {
             self.is_valid = false;
@@ -60,6 +107,24 @@ impl CS2 {
             return;
         }
 
-        self.skin_changer(config);
+        let state = self.game_state();
+        let mut game = std::mem::take(&mut self.game);
+        let map_name = || self.global_vars().map(|g| g.map_name).unwrap_or_default();
+        for event in game.update(state, map_name) {
+            log::info!("{event}");
+            if let GameEvent::MapLoaded { map } = event {
+                self.match_context.map = map;
+                self.match_context.mode = self.game_mode();
+                log::info!("game mode: {:?}", self.match_context.mode);
+            }
+        }
+        self.game = game;
+
+        // the state is a guess without the sign-on state, so it does not gate
+        // the changer, which needs a local player either way
+        let state_known = self.offsets.network_client.sign_on_state.is_some();
+        if self.game.state().in_game() || !state_known {
+            self.skin_changer(config);
+        }
     }
 }
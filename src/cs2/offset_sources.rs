@@ -0,0 +1,437 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{BASE_PATH, OffsetSource, OffsetSourcesConfig, SourceKind},
+    game_data::vpk::crc32,
+};
+
+/// Responses larger than this are rejected, schema dumps are a few MB.
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Validators stored next to a cached download.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched: u64,
+}
+
+/// Loads offset sources in the configured order, caching downloads.
+pub struct SourceLoader<'a> {
+    config: &'a OffsetSourcesConfig,
+    base: PathBuf,
+    cache_dir: PathBuf,
+    game_build: Option<u32>,
+    agent: ureq::Agent,
+}
+
+impl<'a> SourceLoader<'a> {
+    pub fn new(config: &'a OffsetSourcesConfig, game_build: Option<u32>) -> Self {
+        Self::with_paths(config, game_build, BASE_PATH.clone(), BASE_PATH.join("cache"))
+    }
+
+    /// Resolves relative source paths against `base` and caches downloads in `cache_dir`.
+    pub fn with_paths(
+        config: &'a OffsetSourcesConfig,
+        game_build: Option<u32>,
+        base: PathBuf,
+        cache_dir: PathBuf,
+    ) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(config.timeout_secs)))
+            .build()
+            .into();
+        Self {
+            config,
+            base,
+            cache_dir,
+            game_build,
+            agent,
+        }
+    }
+
+    /// Returns the first source of `kind` that loads and that `parse` accepts.
+    pub fn load<T>(&self, kind: SourceKind, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+        for source in self.config.sources.iter().filter(|s| s.kind == kind) {
+            if let (Some(build), Some(game_build)) = (source.build, self.game_build)
+                && build != game_build
+            {
+                log::info!(
+                    "skipping {} for build {build}, game is build {game_build}",
+                    describe(source)
+                );
+                continue;
+            }
+
+            // pinned sources must match their checksum, a download that does
+            // not never replaces the cached copy
+            let verified = |content: &str| {
+                let actual = crc32(content.as_bytes());
+                match source.crc32 {
+                    Some(expected) if expected != actual => {
+                        log::warn!(
+                            "{} has CRC-32 0x{actual:08X}, expected 0x{expected:08X}",
+                            describe(source)
+                        );
+                        None
+                    }
+                    _ => parse(content),
+                }
+            };
+            let content = match (&source.path, &source.url) {
+                (Some(path), _) => self.load_file(path),
+                (None, Some(url)) => self.load_url(url, verified),
+                (None, None) => {
+                    log::warn!("offset source without path or url");
+                    continue;
+                }
+            };
+            let Some(content) = content else {
+                continue;
+            };
+            match verified(&content) {
+                Some(value) => {
+                    log::info!("loaded {:?} from {}", kind, describe(source));
+                    return Some(value);
+                }
+                None => log::warn!("could not parse {}", describe(source)),
+            }
+        }
+        None
+    }
+
+    fn load_file(&self, path: &Path) -> Option<String> {
+        let path = self.base.join(path);
+        std::fs::read_to_string(&path)
+            .map_err(|e| log::debug!("could not read {path:?}: {e}"))
+            .ok()
+    }
+
+    fn cache_paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let name: String = url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' { c } else { '_' })
+            .collect();
+        let name = &name[name.len().saturating_sub(200)..];
+        (
+            self.cache_dir.join(name),
+            self.cache_dir.join(format!("{name}.meta")),
+        )
+    }
+
+    /// Returns the cached copy of `url`, revalidating it with the server once
+    /// it is older than `max_age_secs`. A failed request falls back to the
+    /// stale copy, and downloads that `parse` rejects never replace it.
+    fn load_url<T>(&self, url: &str, parse: impl Fn(&str) -> Option<T>) -> Option<String> {
+        let (data_path, meta_path) = self.cache_paths(url);
+        let cached = std::fs::read_to_string(&data_path).ok();
+        let meta: CacheMeta = std::fs::read_to_string(&meta_path)
+            .ok()
+            .and_then(|m| serde_json::from_str(&m).ok())
+            .unwrap_or_default();
+
+        if self.config.offline {
+            if cached.is_none() {
+                log::debug!("offline, no cached copy of {url}");
+            }
+            return cached;
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if cached.is_some() && now.saturating_sub(meta.fetched) < self.config.max_age_secs {
+            log::debug!("using cached copy of {url}");
+            return cached;
+        }
+
+        log::info!("fetching {url}");
+        let mut request = self.agent.get(url);
+        if cached.is_some() {
+            if let Some(etag) = &meta.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+        }
+
+        let mut response = match request.call() {
+            Ok(response) => response,
+            Err(e) => {
+                log::warn!("failed to fetch {url}: {e}");
+                return cached;
+            }
+        };
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let new_meta = CacheMeta {
+            url: url.to_string(),
+            etag: header("etag"),
+            last_modified: header("last-modified"),
+            fetched: now,
+        };
+
+        if response.status() == 304 {
+            log::debug!("{url} not modified");
+            // a 304 does not have to repeat the validators
+            let new_meta = CacheMeta {
+                etag: new_meta.etag.or(meta.etag),
+                last_modified: new_meta.last_modified.or(meta.last_modified),
+                ..new_meta
+            };
+            self.write_meta(&meta_path, &new_meta);
+            return cached;
+        }
+
+        let content = match response
+            .body_mut()
+            .with_config()
+            .limit(MAX_DOWNLOAD_SIZE)
+            .read_to_string()
+        {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("failed to read response body from {url}: {e}");
+                return cached;
+            }
+        };
+
+        if parse(&content).is_none() {
+            log::warn!("{url} returned invalid data, keeping cached copy");
+            return cached;
+        }
+
+        if let Err(e) = std::fs::create_dir_all(&self.cache_dir)
+            .and_then(|_| std::fs::write(&data_path, &content))
+        {
+            log::warn!("could not cache {url}: {e}");
+        } else {
+            self.write_meta(&meta_path, &new_meta);
+        }
+        Some(content)
+    }
+
+    fn write_meta(&self, path: &Path, meta: &CacheMeta) {
+        if let Ok(out) = serde_json::to_string(meta) {
+            let _ = std::fs::write(path, out);
+        }
+    }
+}
+
+fn describe(source: &OffsetSource) -> String {
+    match (&source.path, &source.url) {
+        (Some(path), _) => format!("{path:?}"),
+        (None, Some(url)) => url.clone(),
+        (None, None) => "<empty source>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread::JoinHandle,
+    };
+
+    use super::*;
+
+    /// What the stand-in server does with one connection.
+    enum Reply {
+        Send(&'static str),
+        /// Holds the connection open without answering.
+        Stall,
+    }
+
+    /// Serves one connection per reply on a local port. The handle returns
+    /// the request headers, lowercased.
+    fn serve(replies: Vec<Reply>) -> (String, JoinHandle<Vec<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/offsets.json", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for reply in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut headers = Vec::new();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                    headers.push(line.trim_end().to_ascii_lowercase());
+                }
+                requests.push(headers);
+                match reply {
+                    Reply::Send(response) => {
+                        let _ = stream.write_all(response.as_bytes());
+                    }
+                    Reply::Stall => std::thread::sleep(Duration::from_secs(2)),
+                }
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn ok(etag: &str, body: &str) -> &'static str {
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nETag: {etag}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        response.leak()
+    }
+
+    const NOT_MODIFIED: &str = "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n";
+
+    fn config(url: &str) -> OffsetSourcesConfig {
+        OffsetSourcesConfig {
+            offline: false,
+            timeout_secs: 1,
+            max_age_secs: 0,
+            sources: vec![OffsetSource {
+                kind: SourceKind::Offsets,
+                path: None,
+                url: Some(url.to_string()),
+                build: None,
+                crc32: None,
+            }],
+        }
+    }
+
+    fn temp_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sources-test-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn load(config: &OffsetSourcesConfig, dir: &Path) -> Option<String> {
+        SourceLoader::with_paths(config, None, dir.to_path_buf(), dir.join("cache"))
+            .load(SourceKind::Offsets, |content| Some(content.to_string()))
+    }
+
+    #[test]
+    fn caches_download_and_revalidates_with_etag() {
+        let dir = temp_dir("etag");
+        let (url, server) = serve(vec![
+            Reply::Send(ok("\"v1\"", "{\"a\":1}")),
+            Reply::Send(NOT_MODIFIED),
+        ]);
+        let config = config(&url);
+
+        assert_eq!(load(&config, &dir).as_deref(), Some("{\"a\":1}"));
+        let loader = SourceLoader::with_paths(&config, None, dir.clone(), dir.join("cache"));
+        let (data_path, meta_path) = loader.cache_paths(&url);
+        assert_eq!(std::fs::read_to_string(data_path).unwrap(), "{\"a\":1}");
+        let meta: CacheMeta =
+            serde_json::from_str(&std::fs::read_to_string(meta_path).unwrap()).unwrap();
+        assert_eq!(meta.etag.as_deref(), Some("\"v1\""));
+
+        // 304 keeps the cached copy
+        assert_eq!(load(&config, &dir).as_deref(), Some("{\"a\":1}"));
+        let requests = server.join().unwrap();
+        assert!(!requests[0].iter().any(|h| h.starts_with("if-none-match")));
+        assert!(requests[1].contains(&"if-none-match: \"v1\"".to_string()));
+    }
+
+    #[test]
+    fn fresh_cache_skips_the_server() {
+        let dir = temp_dir("fresh");
+        let (url, server) = serve(vec![Reply::Send(ok("\"v1\"", "{}"))]);
+        let config = OffsetSourcesConfig {
+            max_age_secs: 3600,
+            ..config(&url)
+        };
+        assert_eq!(load(&config, &dir).as_deref(), Some("{}"));
+        server.join().unwrap();
+        // the server is gone, a second request would fail
+        assert_eq!(load(&config, &dir).as_deref(), Some("{}"));
+    }
+
+    #[test]
+    fn timeout_falls_back_to_cached_copy() {
+        let dir = temp_dir("timeout");
+        let (url, server) = serve(vec![
+            Reply::Send(ok("\"v1\"", "{}")),
+            Reply::Stall,
+            Reply::Stall,
+        ]);
+        let config = config(&url);
+        assert_eq!(load(&config, &dir).as_deref(), Some("{}"));
+
+        let start = std::time::Instant::now();
+        assert_eq!(load(&config, &dir).as_deref(), Some("{}"));
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        std::fs::remove_dir_all(dir.join("cache")).unwrap();
+        assert_eq!(load(&config, &dir), None);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn offline_uses_only_the_cache() {
+        let dir = temp_dir("offline");
+        let (url, server) = serve(vec![Reply::Send(ok("\"v1\"", "{}"))]);
+        assert_eq!(load(&config(&url), &dir).as_deref(), Some("{}"));
+        server.join().unwrap();
+
+        let offline = OffsetSourcesConfig {
+            offline: true,
+            ..config(&url)
+        };
+        assert_eq!(load(&offline, &dir).as_deref(), Some("{}"));
+        let other = OffsetSourcesConfig {
+            offline: true,
+            ..config("http://127.0.0.1:9/other.json")
+        };
+        assert_eq!(load(&other, &dir), None);
+    }
+
+    #[test]
+    fn rejects_content_with_other_checksum() {
+        let dir = temp_dir("crc");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("offsets.json"), "{}").unwrap();
+        let mut config = config("unused");
+        config.sources[0].url = None;
+        config.sources[0].path = Some(PathBuf::from("offsets.json"));
+
+        config.sources[0].crc32 = Some(crc32(b"{}"));
+        assert_eq!(load(&config, &dir).as_deref(), Some("{}"));
+        config.sources[0].crc32 = Some(0);
+        assert_eq!(load(&config, &dir), None);
+    }
+
+    #[test]
+    fn skips_sources_for_other_builds() {
+        let dir = temp_dir("build");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("offsets.json"), "{}").unwrap();
+        let mut config = config("unused");
+        config.sources[0].url = None;
+        config.sources[0].path = Some(PathBuf::from("offsets.json"));
+        config.sources[0].build = Some(100);
+
+        let loader = |build| {
+            SourceLoader::with_paths(&config, build, dir.clone(), dir.join("cache"))
+                .load(SourceKind::Offsets, |content| Some(content.to_string()))
+        };
+        assert_eq!(loader(Some(100)).as_deref(), Some("{}"));
+        assert_eq!(loader(Some(101)), None);
+        assert_eq!(loader(None).as_deref(), Some("{}"));
+    }
+}
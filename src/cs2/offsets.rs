@@ -20,17 +20,21 @@ pub struct InterfaceOffsets {
 pub struct DirectOffsets {
     pub local_player: u64,
     pub network_client: u64,
+    pub global_vars: Option<u64>,
+    pub game_rules: Option<u64>,
 }
 
 #[derive(Debug, Default)]
 pub struct PlayerControllerOffsets {
     pub pawn: u64,
+    pub team: Option<u64>,
 }
 
 #[derive(Debug, Default)]
 pub struct PawnOffsets {
     pub weapon: u64,
     pub weapon_services: u64,
+    pub controller: Option<u64>,
 }
 
 #[derive(Debug, Default)]
@@ -52,26 +56,81 @@ pub struct EntityIdentityOffsets {
 
 #[derive(Debug, Default)]
 pub struct SkinOffsets {
-    pub item_id_high: u64,
-    pub item_id_low: u64,
-    pub account_id: u64,
-    pub entity_quality: u64,
-    pub initialized: u64,
-    pub attribute_list: u64,
-    pub networked_dynamic_attrs: u64,
+    pub item_id_high: Option<u64>,
+    pub item_id_low: Option<u64>,
+    pub account_id: Option<u64>,
+    pub entity_quality: Option<u64>,
+    pub initialized: Option<u64>,
+    pub attribute_list: Option<u64>,
+    pub networked_dynamic_attrs: Option<u64>,
     // Fallback fields - these are the key for client-side skin changing
-    pub fallback_paint_kit: u64,
-    pub fallback_seed: u64,
-    pub fallback_wear: u64,
-    pub fallback_stattrak: u64,
-    pub custom_name: u64,
-    pub original_owner_xuid_low: u64,
-    pub original_owner_xuid_high: u64,
+    pub fallback_paint_kit: Option<u64>,
+    pub fallback_seed: Option<u64>,
+    pub fallback_wear: Option<u64>,
+    pub fallback_stattrak: Option<u64>,
+    pub custom_name: Option<u64>,
+    pub original_owner_xuid_low: Option<u64>,
+    pub original_owner_xuid_high: Option<u64>,
 }
 
 #[derive(Debug, Default)]
 pub struct NetworkGameClientOffsets {
     pub delta_tick: u64,
+    pub sign_on_state: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct GameRulesOffsets {
+    pub warmup_period: Option<u64>,
+    pub round_win_status: Option<u64>,
+}
+
+/// Field offsets in the client's global vars, which are not part of the schema.
+#[derive(Debug)]
+pub struct GlobalVarsOffsets {
+    pub frame_count: u64,
+    pub current_time: u64,
+    pub tick_count: u64,
+    pub map_name: u64,
+}
+
+impl Default for GlobalVarsOffsets {
+    fn default() -> Self {
+        Self {
+            frame_count: 0x04,
+            current_time: 0x30,
+            tick_count: 0x48,
+            map_name: 0x188,
+        }
+    }
+}
+
+/// Layout of the cvar interface's ConVar list and its entries.
+#[derive(Debug)]
+pub struct ConVarOffsets {
+    pub list: u64,
+    pub count: u64,
+    pub entry_size: u64,
+    pub name: u64,
+    pub description: u64,
+    pub ty: u64,
+    pub flags: u64,
+    pub value: u64,
+}
+
+impl Default for ConVarOffsets {
+    fn default() -> Self {
+        Self {
+            list: 0x40,
+            count: 0xA0,
+            entry_size: 0x10,
+            name: 0x00,
+            description: 0x20,
+            ty: 0x28,
+            flags: 0x30,
+            value: 0x40,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -86,4 +145,7 @@ pub struct Offsets {
     pub entity_identity: EntityIdentityOffsets,
     pub skin: SkinOffsets,
     pub network_client: NetworkGameClientOffsets,
+    pub game_rules: GameRulesOffsets,
+    pub global_vars: GlobalVarsOffsets,
+    pub convar: ConVarOffsets,
 }
@@ -1,9 +1,18 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, io, path::Path};
 
-use crate::os::process::Process;
+use serde::{Deserialize, Serialize};
 
+use crate::{constants::cs2, os::process::Process};
+
+/// Default file name of a schema snapshot in `BASE_PATH`.
+pub const SCHEMA_SNAPSHOT_NAME: &str = "schema.json";
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Schema {
-    scopes: HashMap<String, ModuleScope>,
+    scopes: BTreeMap<String, ModuleScope>,
+    /// Loaded from a cs2-dumper file, which has no class sizes.
+    #[serde(skip)]
+    from_dumper: bool,
 }
 
 impl Schema {
@@ -16,31 +25,86 @@ impl Schema {
 
         let type_scopes_len: i32 = process.read(schema_system + 0x1F0);
         let type_scopes_vec: u64 = process.read(schema_system + 0x1F8);
-        let mut scopes = HashMap::new();
+        let mut scopes = BTreeMap::new();
         for i in 0..type_scopes_len as u64 {
             let type_scope_address = process.read(type_scopes_vec + (i * 8));
             let type_scope = ModuleScope::new(process, type_scope_address);
             scopes.insert(type_scope.name.clone(), type_scope);
         }
 
-        Some(Self { scopes })
+        Some(Self {
+            scopes,
+            from_dumper: false,
+        })
+    }
+
+    /// Loads a schema snapshot written with [`Schema::save`], or a cs2-dumper
+    /// schema file such as `client_dll.json`.
+    ///
+    /// cs2-dumper files carry neither class sizes nor field types, so those
+    /// are left as `0` and an empty type name. Their Windows module names
+    /// (`client.dll`) are stored under the linux library name.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_json(&content)
+    }
+
+    /// Parses either snapshot format, see [`Schema::load`].
+    pub fn from_json(content: &str) -> io::Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(content).map_err(io::Error::other)?;
+        if value.get("scopes").is_some() {
+            return serde_json::from_value(value).map_err(io::Error::other);
+        }
+
+        let modules: BTreeMap<String, DumperModule> =
+            serde_json::from_value(value).map_err(io::Error::other)?;
+        let scopes = modules
+            .into_iter()
+            .map(|(name, module)| {
+                let name = cs2::WINDOWS_MODULES
+                    .iter()
+                    .find(|(_, windows)| *windows == name)
+                    .map_or(name, |(linux, _)| linux.to_string());
+                let scope = ModuleScope::from_dumper(name.clone(), module);
+                (name, scope)
+            })
+            .collect();
+        Ok(Self {
+            scopes,
+            from_dumper: true,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let out = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, out)
     }
 
     pub fn get_library(&self, library: &str) -> Option<&ModuleScope> {
         self.scopes.get(library)
     }
+
+    pub fn scopes(&self) -> &BTreeMap<String, ModuleScope> {
+        &self.scopes
+    }
+
+    /// Whether classes have their size, cs2-dumper files do not.
+    pub fn has_class_sizes(&self) -> bool {
+        !self.from_dumper
+    }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ModuleScope {
     name: String,
-    classes: HashMap<String, Class>,
+    classes: BTreeMap<String, Class>,
 }
 
 impl ModuleScope {
     fn new(process: &Process, address: u64) -> Self {
         let name = process.read_string_uncached(address + 0x08);
 
-        let mut classes = HashMap::new();
+        let mut classes = BTreeMap::new();
         // has 1024 buckets
         let hash_vector = address + 0x560 + 0x90;
         for i in 0..1024 {
@@ -71,26 +135,51 @@ impl ModuleScope {
         Self { name, classes }
     }
 
-    pub fn get(&self, class: &str, field: &str) -> Option<u64> {
-        let Some(c) = self.classes.get(class) else {
-            log::warn!("could not find class {class}");
-            return None;
-        };
-        let f = c.get(field);
-        if f.is_none() {
-            log::warn!("could not find field {field} in class {class}");
-        }
-        f
+    fn from_dumper(name: String, module: DumperModule) -> Self {
+        let classes = module
+            .classes
+            .into_iter()
+            .map(|(class_name, class)| {
+                let fields = class
+                    .fields
+                    .into_iter()
+                    .map(|(field_name, offset)| {
+                        let field = Field {
+                            offset,
+                            type_name: String::new(),
+                        };
+                        (field_name, field)
+                    })
+                    .collect();
+                let class = Class {
+                    name: class_name.clone(),
+                    fields,
+                    size: 0,
+                };
+                (class_name, class)
+            })
+            .collect();
+        Self { name, classes }
+    }
+
+    /// Looks up a field without logging when it is missing.
+    pub fn field(&self, class: &str, field: &str) -> Option<&Field> {
+        self.classes.get(class)?.fields.get(field)
     }
 
     pub fn get_class(&self, class: &str) -> Option<&Class> {
         self.classes.get(class)
     }
+
+    pub fn classes(&self) -> &BTreeMap<String, Class> {
+        &self.classes
+    }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Class {
     name: String,
-    fields: HashMap<String, u64>,
+    fields: BTreeMap<String, Field>,
     size: i32,
 }
 
@@ -98,7 +187,7 @@ impl Class {
     fn new(process: &Process, address: u64) -> Self {
         let name = process.read_string_uncached(process.read(address + 0x08));
 
-        let mut fields = HashMap::new();
+        let mut fields = BTreeMap::new();
         let field_count: i16 = process.read(address + 0x1C);
         let size = process.read(address + 0x18);
         if !(0..=20000).contains(&field_count) {
@@ -106,31 +195,52 @@ impl Class {
         }
         let fields_vec: u64 = process.read(address + 0x28);
         for i in 0..field_count as u64 {
-            let field = Field::new(process, fields_vec + (0x20 * i));
-            fields.insert(field.name, field.offset);
+            let (name, field) = Field::new(process, fields_vec + (0x20 * i));
+            fields.insert(name, field);
         }
         Self { name, fields, size }
     }
 
-    fn get(&self, field: &str) -> Option<u64> {
-        self.fields.get(field).copied()
-    }
-
     pub fn size(&self) -> i32 {
         self.size
     }
+
+    pub fn fields(&self) -> &BTreeMap<String, Field> {
+        &self.fields
+    }
 }
 
-struct Field {
-    name: String,
-    offset: u64,
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Field {
+    pub offset: u64,
+    pub type_name: String,
 }
 
 impl Field {
-    fn new(process: &Process, address: u64) -> Self {
+    fn new(process: &Process, address: u64) -> (String, Self) {
         let name = process.read_string_uncached(process.read(address));
+        // CSchemaType: vtable, then the type name as a CUtlString
+        let schema_type: u64 = process.read(address + 0x08);
+        let type_name = if schema_type == 0 {
+            String::new()
+        } else {
+            process.read_string_uncached(process.read(schema_type + 0x08))
+        };
         let offset = process.read::<i32>(address + 0x10) as u64;
 
-        Self { name, offset }
+        (name, Self { offset, type_name })
     }
 }
+
+/// One module of a cs2-dumper schema file (`client_dll.json`).
+#[derive(Deserialize)]
+struct DumperModule {
+    #[serde(default)]
+    classes: BTreeMap<String, DumperClass>,
+}
+
+#[derive(Deserialize)]
+struct DumperClass {
+    #[serde(default)]
+    fields: BTreeMap<String, u64>,
+}
@@ -0,0 +1,374 @@
+use std::fmt::Display;
+
+use crate::{
+    constants::cs2,
+    cs2::{
+        find_offsets::{SCHEMA_CLASS_SIZES, SCHEMA_FIELDS},
+        schema::{Class, Schema},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    ClassAdded,
+    ClassRemoved,
+    ClassResized { old: i32, new: i32 },
+    FieldAdded { offset: u64 },
+    FieldRemoved { offset: u64 },
+    FieldMoved { old: u64, new: u64 },
+    FieldRetyped { old: String, new: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub scope: String,
+    pub class: String,
+    pub field: Option<String>,
+    pub kind: ChangeKind,
+}
+
+impl Change {
+    /// Returns `Some(required)` if `find_offsets` reads the changed class or field.
+    pub fn referenced(&self) -> Option<bool> {
+        if self.scope != cs2::CLIENT_LIB {
+            return None;
+        }
+        match &self.field {
+            Some(field) => SCHEMA_FIELDS
+                .iter()
+                .find(|f| f.class == self.class && f.field == field)
+                .map(|f| f.required),
+            None => {
+                if SCHEMA_CLASS_SIZES.contains(&self.class.as_str()) {
+                    return Some(true);
+                }
+                SCHEMA_FIELDS
+                    .iter()
+                    .filter(|f| f.class == self.class)
+                    .map(|f| f.required)
+                    .reduce(|a, b| a || b)
+            }
+        }
+    }
+
+    /// A required field moved or disappeared, so the saved offsets are no longer valid.
+    pub fn is_breaking(&self) -> bool {
+        if self.referenced() != Some(true) {
+            return false;
+        }
+        match self.kind {
+            ChangeKind::ClassRemoved
+            | ChangeKind::FieldRemoved { .. }
+            | ChangeKind::FieldMoved { .. } => true,
+            ChangeKind::ClassResized { .. } => SCHEMA_CLASS_SIZES.contains(&self.class.as_str()),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let marker = match self.referenced() {
+            Some(true) if self.is_breaking() => "!!",
+            Some(_) => "* ",
+            None => "  ",
+        };
+        let name = match &self.field {
+            Some(field) => format!("{}::{}::{}", self.scope, self.class, field),
+            None => format!("{}::{}", self.scope, self.class),
+        };
+        match &self.kind {
+            ChangeKind::ClassAdded => write!(f, "{marker} + class {name}"),
+            ChangeKind::ClassRemoved => write!(f, "{marker} - class {name}"),
+            ChangeKind::ClassResized { old, new } => {
+                write!(f, "{marker} ~ class {name} size 0x{old:X} -> 0x{new:X}")
+            }
+            ChangeKind::FieldAdded { offset } => write!(f, "{marker} + field {name} @ 0x{offset:X}"),
+            ChangeKind::FieldRemoved { offset } => {
+                write!(f, "{marker} - field {name} @ 0x{offset:X}")
+            }
+            ChangeKind::FieldMoved { old, new } => {
+                write!(f, "{marker} ~ field {name} 0x{old:X} -> 0x{new:X}")
+            }
+            ChangeKind::FieldRetyped { old, new } => {
+                write!(f, "{marker} ~ field {name} type {old} -> {new}")
+            }
+        }
+    }
+}
+
+/// Compares two schemas and lists every class and field difference. Class
+/// sizes and field types are only compared when both schemas have them,
+/// cs2-dumper files do not.
+pub fn diff(old: &Schema, new: &Schema) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let empty = Default::default();
+    let sizes = old.has_class_sizes() && new.has_class_sizes();
+
+    let scopes = old.scopes().keys().chain(new.scopes().keys());
+    let mut scopes: Vec<&String> = scopes.collect();
+    scopes.sort();
+    scopes.dedup();
+
+    for scope in scopes {
+        let old_classes = old.get_library(scope).map_or(&empty, |s| s.classes());
+        let new_classes = new.get_library(scope).map_or(&empty, |s| s.classes());
+
+        for (name, class) in old_classes {
+            match new_classes.get(name) {
+                Some(new_class) => diff_class(scope, name, (class, new_class), sizes, &mut changes),
+                None => changes.push(Change {
+                    scope: scope.clone(),
+                    class: name.clone(),
+                    field: None,
+                    kind: ChangeKind::ClassRemoved,
+                }),
+            }
+        }
+        for name in new_classes.keys() {
+            if !old_classes.contains_key(name) {
+                changes.push(Change {
+                    scope: scope.clone(),
+                    class: name.clone(),
+                    field: None,
+                    kind: ChangeKind::ClassAdded,
+                });
+            }
+        }
+    }
+
+    changes
+}
+
+fn diff_class(
+    scope: &str,
+    name: &str,
+    (old, new): (&Class, &Class),
+    sizes: bool,
+    changes: &mut Vec<Change>,
+) {
+    let change = |field: Option<&String>, kind| Change {
+        scope: scope.to_string(),
+        class: name.to_string(),
+        field: field.cloned(),
+        kind,
+    };
+
+    if sizes && old.size() != new.size() {
+        changes.push(change(
+            None,
+            ChangeKind::ClassResized {
+                old: old.size(),
+                new: new.size(),
+            },
+        ));
+    }
+
+    for (field_name, field) in old.fields() {
+        let Some(new_field) = new.fields().get(field_name) else {
+            changes.push(change(
+                Some(field_name),
+                ChangeKind::FieldRemoved {
+                    offset: field.offset,
+                },
+            ));
+            continue;
+        };
+        if field.offset != new_field.offset {
+            changes.push(change(
+                Some(field_name),
+                ChangeKind::FieldMoved {
+                    old: field.offset,
+                    new: new_field.offset,
+                },
+            ));
+        }
+        let typed = !field.type_name.is_empty() && !new_field.type_name.is_empty();
+        if typed && field.type_name != new_field.type_name {
+            changes.push(change(
+                Some(field_name),
+                ChangeKind::FieldRetyped {
+                    old: field.type_name.clone(),
+                    new: new_field.type_name.clone(),
+                },
+            ));
+        }
+    }
+    for (field_name, field) in new.fields() {
+        if !old.fields().contains_key(field_name) {
+            changes.push(change(
+                Some(field_name),
+                ChangeKind::FieldAdded {
+                    offset: field.offset,
+                },
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(classes: &str) -> Schema {
+        let json = format!(
+            r#"{{"scopes": {{"libclient.so": {{"name": "libclient.so", "classes": {{{classes}}}}}}}}}"#
+        );
+        Schema::from_json(&json).unwrap()
+    }
+
+    fn class_json(name: &str, size: i32, fields: &[(&str, u64, &str)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(field, offset, type_name)| {
+                format!(r#""{field}": {{"offset": {offset}, "type_name": "{type_name}"}}"#)
+            })
+            .collect();
+        format!(
+            r#""{name}": {{"name": "{name}", "size": {size}, "fields": {{{}}}}}"#,
+            fields.join(", ")
+        )
+    }
+
+    fn kinds(changes: &[Change]) -> Vec<(&str, Option<&str>, &ChangeKind)> {
+        changes
+            .iter()
+            .map(|c| (c.class.as_str(), c.field.as_deref(), &c.kind))
+            .collect()
+    }
+
+    #[test]
+    fn lists_class_and_field_changes() {
+        let old = snapshot(
+            &[
+                class_json(
+                    "C_EconItemView",
+                    0x1F0,
+                    &[
+                        ("m_iItemDefinitionIndex", 0x1BA, "uint16"),
+                        ("m_iAccountID", 0x1D8, "uint32"),
+                        ("m_bInitialized", 0x1E8, "bool"),
+                    ],
+                ),
+                class_json("C_Gone", 8, &[]),
+            ]
+            .join(", "),
+        );
+        let new = snapshot(
+            &[
+                class_json(
+                    "C_EconItemView",
+                    0x200,
+                    &[
+                        ("m_iItemDefinitionIndex", 0x1C2, "uint16"),
+                        ("m_iAccountID", 0x1D8, "uint64"),
+                        ("m_iEntityQuality", 0x1BC, "int32"),
+                    ],
+                ),
+                class_json("C_New", 8, &[]),
+            ]
+            .join(", "),
+        );
+
+        let changes = diff(&old, &new);
+        assert_eq!(
+            kinds(&changes),
+            [
+                (
+                    "C_EconItemView",
+                    None,
+                    &ChangeKind::ClassResized {
+                        old: 0x1F0,
+                        new: 0x200
+                    }
+                ),
+                (
+                    "C_EconItemView",
+                    Some("m_bInitialized"),
+                    &ChangeKind::FieldRemoved { offset: 0x1E8 }
+                ),
+                (
+                    "C_EconItemView",
+                    Some("m_iAccountID"),
+                    &ChangeKind::FieldRetyped {
+                        old: "uint32".to_string(),
+                        new: "uint64".to_string(),
+                    }
+                ),
+                (
+                    "C_EconItemView",
+                    Some("m_iItemDefinitionIndex"),
+                    &ChangeKind::FieldMoved {
+                        old: 0x1BA,
+                        new: 0x1C2
+                    }
+                ),
+                (
+                    "C_EconItemView",
+                    Some("m_iEntityQuality"),
+                    &ChangeKind::FieldAdded { offset: 0x1BC }
+                ),
+                ("C_Gone", None, &ChangeKind::ClassRemoved),
+                ("C_New", None, &ChangeKind::ClassAdded),
+            ]
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn marks_changes_to_read_fields() {
+        let change = |field: &str, kind| Change {
+            scope: cs2::CLIENT_LIB.to_string(),
+            class: "C_EconItemView".to_string(),
+            field: Some(field.to_string()),
+            kind,
+        };
+        let moved = ChangeKind::FieldMoved {
+            old: 0x1BA,
+            new: 0x1C2,
+        };
+        let read = change("m_iItemDefinitionIndex", moved.clone());
+        assert_eq!(read.referenced(), Some(true));
+        assert!(read.is_breaking());
+        assert!(read.to_string().starts_with("!!"));
+
+        let added = change(
+            "m_iItemDefinitionIndex",
+            ChangeKind::FieldAdded { offset: 0x1BA },
+        );
+        assert!(!added.is_breaking());
+        let optional = change("m_bInitialized", moved.clone());
+        assert_eq!(optional.referenced(), Some(false));
+        assert!(!optional.is_breaking());
+        assert!(optional.to_string().starts_with("* "));
+        let unread = change("m_iInventoryPosition", moved);
+        assert_eq!(unread.referenced(), None);
+        let engine = Change {
+            scope: cs2::ENGINE_LIB.to_string(),
+            ..read
+        };
+        assert_eq!(engine.referenced(), None);
+    }
+
+    #[test]
+    fn ignores_sizes_and_types_a_dumper_schema_lacks() {
+        let dumper = Schema::from_json(include_str!("testdata/client_dll.json")).unwrap();
+        let scope = dumper.get_library(cs2::CLIENT_LIB).unwrap();
+        let classes: Vec<String> = scope
+            .classes()
+            .iter()
+            .map(|(name, class)| {
+                let fields: Vec<(&str, u64, &str)> = class
+                    .fields()
+                    .iter()
+                    .map(|(field, f)| (field.as_str(), f.offset, "int32"))
+                    .collect();
+                class_json(name, 0x100, &fields)
+            })
+            .collect();
+        let live = snapshot(&classes.join(", "));
+
+        assert!(diff(&dumper, &live).is_empty());
+        assert!(diff(&live, &dumper).is_empty());
+    }
+}
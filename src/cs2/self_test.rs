@@ -0,0 +1,142 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+use crate::{
+    constants::cs2,
+    cs2::{CS2, entity::player::Player},
+};
+
+/// `CEntityIdentity` has been 0x70-0x78 bytes for as long as CS2 exists.
+const ENTITY_IDENTITY_SIZE: RangeInclusive<i32> = 0x60..=0x100;
+
+#[derive(Debug)]
+pub enum CheckStatus {
+    Pass,
+    Fail(String),
+    /// The check needs state the game is not in, e.g. a local player.
+    Skip(String),
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+impl Check {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, CheckStatus::Fail(_))
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.status {
+            CheckStatus::Pass => write!(f, "PASS  {}", self.name),
+            CheckStatus::Fail(reason) => write!(f, "FAIL  {:<36} {reason}", self.name),
+            CheckStatus::Skip(reason) => write!(f, "SKIP  {:<36} {reason}", self.name),
+        }
+    }
+}
+
+impl CS2 {
+    /// Checks that the found offsets point where they should.
+    pub fn self_test(&self) -> Vec<Check> {
+        let mut checks = Vec::new();
+        let mut check = |name, status| checks.push(Check { name, status });
+
+        check(
+            "interface.resource",
+            self.in_module(self.offsets.interface.resource, cs2::ENGINE_LIB),
+        );
+        check(
+            "interface.cvar",
+            self.in_module(self.offsets.interface.cvar, cs2::TIER0_LIB),
+        );
+        check(
+            "interface.input",
+            self.in_module(self.offsets.interface.input, cs2::INPUT_LIB),
+        );
+        check(
+            "direct.local_player",
+            self.in_module(self.offsets.direct.local_player, cs2::CLIENT_LIB),
+        );
+        check(
+            "direct.network_client",
+            self.in_module(self.offsets.direct.network_client, cs2::ENGINE_LIB),
+        );
+
+        let entity_list = self.offsets.interface.entity;
+        check(
+            "interface.entity",
+            match self.process.mapping_of(entity_list) {
+                Some(mapping) if mapping.is_heap() => CheckStatus::Pass,
+                Some(mapping) => CheckStatus::Fail(format!(
+                    "0x{entity_list:X} is in {} ({}), not heap",
+                    mapping.path, mapping.perms
+                )),
+                None => CheckStatus::Fail(format!("0x{entity_list:X} is not mapped")),
+            },
+        );
+
+        let size = self.offsets.entity_identity.size;
+        check(
+            "entity_identity.size",
+            if ENTITY_IDENTITY_SIZE.contains(&size) {
+                CheckStatus::Pass
+            } else {
+                CheckStatus::Fail(format!(
+                    "0x{size:X} outside 0x{:X}-0x{:X}",
+                    ENTITY_IDENTITY_SIZE.start(),
+                    ENTITY_IDENTITY_SIZE.end()
+                ))
+            },
+        );
+
+        let controller: u64 = self.process.read(self.offsets.direct.local_player);
+        check(
+            "local controller vtable",
+            if controller == 0 {
+                CheckStatus::Skip("no local player".to_string())
+            } else {
+                let vtable: u64 = self.process.read(controller);
+                self.in_module(vtable, cs2::CLIENT_LIB)
+            },
+        );
+
+        check("local pawn handle", self.check_pawn_handle());
+
+        checks
+    }
+
+    fn in_module(&self, address: u64, module: &str) -> CheckStatus {
+        match self.process.mapping_of(address) {
+            Some(mapping) if mapping.path.ends_with(module) => CheckStatus::Pass,
+            Some(mapping) if mapping.path.is_empty() => {
+                CheckStatus::Fail(format!("0x{address:X} is in anonymous memory, not {module}"))
+            }
+            Some(mapping) => {
+                CheckStatus::Fail(format!("0x{address:X} is in {}, not {module}", mapping.path))
+            }
+            None => CheckStatus::Fail(format!("0x{address:X} is not mapped")),
+        }
+    }
+
+    /// controller -> m_hPawn -> pawn -> m_hController must lead back to the controller.
+    fn check_pawn_handle(&self) -> CheckStatus {
+        let Some(controller_field) = self.offsets.pawn.controller else {
+            return CheckStatus::Skip("pawn.controller offset not found".to_string());
+        };
+        let Some(player) = Player::local_player(self) else {
+            return CheckStatus::Skip("no local player".to_string());
+        };
+        let handle: i32 = self.process.read(player.pawn + controller_field);
+        match Player::get_entity(self, handle) {
+            Some(controller) if controller == player.controller => CheckStatus::Pass,
+            Some(controller) => CheckStatus::Fail(format!(
+                "pawn resolves to controller 0x{controller:X}, expected 0x{:X}",
+                player.controller
+            )),
+            None => CheckStatus::Fail(format!("pawn controller handle 0x{handle:X} does not resolve")),
+        }
+    }
+}
@@ -1,9 +1,103 @@
 use crate::{
-    config::SkinChangerConfig,
-    cs2::{entity::weapon::Weapon, CS2},
+    config::{SkinChangerConfig, WeaponSkinConfig},
+    cs2::{
+        CS2,
+        entity::{player::Player, weapon::Weapon},
+        game_state::GameState,
+        loadout::{GameMode, MatchContext, Team},
+        offsets::SkinOffsets,
+    },
+    game_data,
 };
 
+/// Which skin features can work with the offsets found at setup.
+///
+/// `paint_kit` is the core feature: without `m_iItemIDHigh` and
+/// `m_nFallbackPaintKit` nothing can be applied. Every other feature is
+/// skipped on its own when its fields are missing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SkinFeatures {
+    pub paint_kit: bool,
+    pub seed: bool,
+    pub wear: bool,
+    pub stattrak: bool,
+    pub quality: bool,
+    pub name_tag: bool,
+    pub ownership: bool,
+}
+
+impl SkinFeatures {
+    pub fn new(offsets: &SkinOffsets) -> Self {
+        Self {
+            paint_kit: offsets.item_id_high.is_some() && offsets.fallback_paint_kit.is_some(),
+            seed: offsets.fallback_seed.is_some(),
+            wear: offsets.fallback_wear.is_some(),
+            stattrak: offsets.fallback_stattrak.is_some(),
+            quality: offsets.entity_quality.is_some(),
+            name_tag: offsets.custom_name.is_some(),
+            ownership: offsets.account_id.is_some()
+                || offsets.original_owner_xuid_low.is_some()
+                || offsets.original_owner_xuid_high.is_some(),
+        }
+    }
+
+    fn list(&self) -> [(&'static str, bool); 7] {
+        [
+            ("paint kit", self.paint_kit),
+            ("seed", self.seed),
+            ("wear", self.wear),
+            ("stattrak", self.stattrak),
+            ("quality", self.quality),
+            ("name tag", self.name_tag),
+            ("ownership", self.ownership),
+        ]
+    }
+
+    pub fn log(&self) {
+        for (name, available) in self.list() {
+            if available {
+                log::debug!("skin feature {name}: available");
+            } else {
+                log::warn!("skin feature {name}: unavailable, skipping");
+            }
+        }
+        if !self.paint_kit {
+            log::error!("skin changer disabled: paint kit fields not found");
+        }
+    }
+
+    /// Features `skin` asks for that cannot be applied.
+    fn skipped(&self, skin: &WeaponSkinConfig) -> Vec<&'static str> {
+        let mut skipped = Vec::new();
+        if skin.seed != 0 && !self.seed {
+            skipped.push("seed");
+        }
+        if skin.wear.value() != 0.0 && !self.wear {
+            skipped.push("wear");
+        }
+        if skin.stattrak >= 0 && !self.stattrak {
+            skipped.push("stattrak");
+        }
+        if !skin.name_tag.is_empty() && !self.name_tag {
+            skipped.push("name tag");
+        }
+        skipped
+    }
+}
+
 impl CS2 {
+    /// Mode of the current map, from the `game_type` and `game_mode` ConVars.
+    pub(crate) fn game_mode(&self) -> Option<GameMode> {
+        let game_type = self.convar("game_type")?.value.as_int()?;
+        let game_mode = self.convar("game_mode")?.value.as_int()?;
+        GameMode::from_convars(game_type, game_mode)
+    }
+
+    fn team(&self, player: &Player) -> Option<Team> {
+        let team = self.offsets.controller.team?;
+        Team::from_team_num(self.process.read(player.controller + team))
+    }
+
     /// Applies skin changes to weapons using the fallback field system.
     ///
     /// CS2 has fallback fields on C_EconEntity that override inventory data:
@@ -19,20 +113,25 @@ impl CS2 {
             return;
         }
 
-        // Validate required offsets
-        if self.offsets.skin.item_id_high == 0 || self.offsets.skin.fallback_paint_kit == 0 {
-            log::trace!(
-                "Skin changer: missing offsets - item_id_high={}, fallback_paint_kit={}",
-                self.offsets.skin.item_id_high,
-                self.offsets.skin.fallback_paint_kit
-            );
+        if !self.skin_features.paint_kit {
             return;
         }
 
-        let Some(local_player) = crate::cs2::entity::player::Player::local_player(self) else {
+        let Some(local_player) = Player::local_player(self) else {
             return;
         };
 
+        let context = MatchContext {
+            team: self.team(&local_player),
+            warmup: self.game.state() == GameState::Warmup,
+            ..self.match_context.clone()
+        };
+        let loadout = config.active_loadout(&context);
+        if loadout != self.active_loadout.as_deref() {
+            log::info!("loadout {} ({context})", loadout.unwrap_or("default"));
+            self.active_loadout = loadout.map(str::to_string);
+        }
+
         // Get active weapon directly from pawn (this is the weapon entity pointer)
         let active_weapon: u64 = self
             .process
@@ -40,7 +139,7 @@ impl CS2 {
 
         // Apply to active weapon
         if active_weapon != 0 {
-            self.apply_skin_to_weapon(active_weapon, config);
+            self.apply_skin_to_weapon(active_weapon, config, loadout);
         }
 
         // Get weapon services to iterate all weapons in inventory
@@ -73,7 +172,7 @@ impl CS2 {
             let weapon_index = (weapon_handle as u64) & 0xFFF;
 
             let Some(weapon_entity) =
-                crate::cs2::entity::player::Player::get_client_entity(self, weapon_index)
+                Player::get_client_entity(self, weapon_index)
             else {
                 continue;
             };
@@ -82,8 +181,9 @@ impl CS2 {
                 continue;
             }
 
-            self.apply_skin_to_weapon(weapon_entity, config);
+            self.apply_skin_to_weapon(weapon_entity, config, loadout);
         }
+        self.reapply.clear();
 
         // Note: We don't call force_full_update here because it can trigger the game
         // to reload weapon data from inventory, which overwrites our fallback values.
@@ -105,7 +205,12 @@ impl CS2 {
         }
     }
 
-    fn apply_skin_to_weapon(&self, weapon_entity: u64, config: &SkinChangerConfig) -> bool {
+    fn apply_skin_to_weapon(
+        &self,
+        weapon_entity: u64,
+        config: &SkinChangerConfig,
+        loadout: Option<&str>,
+    ) -> bool {
         // Validate weapon entity
         if weapon_entity == 0 {
             return false;
@@ -117,12 +222,20 @@ impl CS2 {
             return false;
         }
 
+        let features = self.skin_features;
+        let (Some(item_id_high), Some(fallback_paint_kit)) = (
+            self.offsets.skin.item_id_high,
+            self.offsets.skin.fallback_paint_kit,
+        ) else {
+            return false;
+        };
+
         // Check if we have a skin config for this weapon
-        let Some(skin_config) = config.skins.get(&weapon) else {
+        let Some(skin_config) = config.skin(&weapon, loadout) else {
             return false;
         };
 
-        if !skin_config.enabled || skin_config.paint_kit <= 0 {
+        if !skin_config.enabled || skin_config.paint_kit.id() <= 0 {
             return false;
         }
 
@@ -134,20 +247,30 @@ impl CS2 {
         // Check current values to determine if we need to apply
         let current_paint_kit: i32 =
             self.process
-                .read(weapon_entity + self.offsets.skin.fallback_paint_kit);
+                .read(weapon_entity + fallback_paint_kit);
 
-        let current_item_id_high: i32 = self.process.read(econ_item_view + self.offsets.skin.item_id_high);
+        let current_item_id_high: i32 = self.process.read(econ_item_view + item_id_high);
 
         // Only apply if values are incorrect (optimization to avoid unnecessary writes)
-        // But we still check every frame to catch resets immediately
-        if current_item_id_high == -1 && current_paint_kit == skin_config.paint_kit {
+        // But we still check every frame to catch resets immediately, and
+        // rewrite weapons whose config changed
+        if current_item_id_high == -1
+            && current_paint_kit == skin_config.paint_kit.id()
+            && !self.reapply.contains(&weapon)
+            && !weapon.fallback().is_some_and(|any| self.reapply.contains(&any))
+        {
             return false;
         }
 
         // Log when values were reset (most common case after death/round change)
-        if current_item_id_high != -1 || current_paint_kit != skin_config.paint_kit {
+        if current_item_id_high != -1 || current_paint_kit != skin_config.paint_kit.id() {
             log::info!("Applying skin: Entity=0x{:X}, Weapon={:?}, PaintKit={}->{}, ItemIdHigh={}->-1", 
-                weapon_entity, weapon, current_paint_kit, skin_config.paint_kit, current_item_id_high);
+                weapon_entity, weapon, current_paint_kit, skin_config.paint_kit.id(), current_item_id_high);
+            log::info!("  {}", game_data::current().skin_name(&weapon, skin_config.paint_kit.id(), features.stattrak && skin_config.stattrak >= 0));
+            let skipped = features.skipped(skin_config);
+            if !skipped.is_empty() {
+                log::info!("  skipped unavailable features: {}", skipped.join(", "));
+            }
         }
 
         // Based on external CS2 skin changer implementations:
@@ -158,92 +281,114 @@ impl CS2 {
         
         // STEP 1: Set ItemIDHigh and ItemIDLow to -1 FIRST
         // This prevents the game from reading inventory data while we set fallback values
-        if self.offsets.skin.item_id_low != 0 {
+        if let Some(item_id_low) = self.offsets.skin.item_id_low {
             self.process
-                .write(econ_item_view + self.offsets.skin.item_id_low, -1i32);
+                .write(econ_item_view + item_id_low, -1i32);
         }
         
         self.process
-            .write(econ_item_view + self.offsets.skin.item_id_high, -1i32);
+            .write(econ_item_view + item_id_high, -1i32);
 
         // STEP 2: Set all fallback values on C_EconEntity
         // These are the actual skin properties the game will use
         self.process.write(
-            weapon_entity + self.offsets.skin.fallback_paint_kit,
-            skin_config.paint_kit,
+            weapon_entity + fallback_paint_kit,
+            skin_config.paint_kit.id(),
         );
 
-        if self.offsets.skin.fallback_seed != 0 {
+        if features.seed
+            && let Some(fallback_seed) = self.offsets.skin.fallback_seed
+        {
             self.process.write(
-                weapon_entity + self.offsets.skin.fallback_seed,
+                weapon_entity + fallback_seed,
                 skin_config.seed,
             );
         }
 
-        if self.offsets.skin.fallback_wear != 0 {
+        if features.wear
+            && let Some(fallback_wear) = self.offsets.skin.fallback_wear
+        {
             self.process.write(
-                weapon_entity + self.offsets.skin.fallback_wear,
-                skin_config.wear,
+                weapon_entity + fallback_wear,
+                skin_config.wear.value(),
             );
         }
 
-        if self.offsets.skin.fallback_stattrak != 0 {
+        if features.stattrak
+            && let Some(fallback_stattrak) = self.offsets.skin.fallback_stattrak
+        {
             self.process.write(
-                weapon_entity + self.offsets.skin.fallback_stattrak,
+                weapon_entity + fallback_stattrak,
                 skin_config.stattrak,
             );
         }
 
         // STEP 3: Set additional CEconItemView fields for proper skin display
-        if self.offsets.skin.account_id != 0 {
-            self.process
-                .write(econ_item_view + self.offsets.skin.account_id, 1u32);
+        if features.name_tag
+            && !skin_config.name_tag.is_empty()
+            && let Some(custom_name) = self.offsets.skin.custom_name
+        {
+            // m_szCustomName is a char[161], keep room for the terminator
+            let mut name = [0u8; 161];
+            let bytes = skin_config.name_tag.as_bytes();
+            let len = bytes.len().min(name.len() - 1);
+            name[..len].copy_from_slice(&bytes[..len]);
+            self.process.write_bytes(econ_item_view + custom_name, &name);
         }
 
-        // Set entity quality for StatTrak (9 = StatTrak quality)
-        if skin_config.stattrak >= 0 && self.offsets.skin.entity_quality != 0 {
+        if features.ownership
+            && let Some(account_id) = self.offsets.skin.account_id
+        {
             self.process
-                .write(econ_item_view + self.offsets.skin.entity_quality, 9i32);
+                .write(econ_item_view + account_id, 1u32);
         }
 
-        // Set to normal quality if not StatTrak
-        else if self.offsets.skin.entity_quality != 0 {
+        // Set entity quality for StatTrak (9 = StatTrak quality), normal quality otherwise
+        if features.quality
+            && let Some(entity_quality) = self.offsets.skin.entity_quality
+        {
+            let stattrak = features.stattrak && skin_config.stattrak >= 0;
+            let quality = if stattrak { 9i32 } else { 0i32 };
             self.process
-                .write(econ_item_view + self.offsets.skin.entity_quality, 0i32);
+                .write(econ_item_view + entity_quality, quality);
         }
 
         // STEP 4: Set original owner XUID fields on C_EconEntity
         // These help prevent the game from resetting skins by indicating ownership
-        if self.offsets.skin.original_owner_xuid_low != 0 {
+        if features.ownership
+            && let Some(original_owner_xuid_low) = self.offsets.skin.original_owner_xuid_low
+        {
             self.process
-                .write(weapon_entity + self.offsets.skin.original_owner_xuid_low, 1u32);
+                .write(weapon_entity + original_owner_xuid_low, 1u32);
         }
-        if self.offsets.skin.original_owner_xuid_high != 0 {
+        if features.ownership
+            && let Some(original_owner_xuid_high) = self.offsets.skin.original_owner_xuid_high
+        {
             self.process
-                .write(weapon_entity + self.offsets.skin.original_owner_xuid_high, 0u32);
+                .write(weapon_entity + original_owner_xuid_high, 0u32);
         }
 
         // STEP 5: CRITICAL - Re-set ItemIDHigh to -1 after all writes
         // The game might reset it during our writes, so we set it again
         self.process
-            .write(econ_item_view + self.offsets.skin.item_id_high, -1i32);
+            .write(econ_item_view + item_id_high, -1i32);
 
         // STEP 6: Verify ItemIDHigh is still -1 and fix if needed
         // Some implementations check multiple times to ensure persistence
-        let verify_item_id_high: i32 = self.process.read(econ_item_view + self.offsets.skin.item_id_high);
+        let verify_item_id_high: i32 = self.process.read(econ_item_view + item_id_high);
         if verify_item_id_high != -1 {
             // Game reset it, try again
             self.process
-                .write(econ_item_view + self.offsets.skin.item_id_high, -1i32);
+                .write(econ_item_view + item_id_high, -1i32);
         }
 
         // STEP 7: Toggle initialized flag to force re-initialization
         // This ensures the game recognizes the changes immediately
-        if self.offsets.skin.initialized != 0 {
+        if let Some(initialized) = self.offsets.skin.initialized {
             self.process
-                .write(econ_item_view + self.offsets.skin.initialized, 0u8);
+                .write(econ_item_view + initialized, 0u8);
             self.process
-                .write(econ_item_view + self.offsets.skin.initialized, 1u8);
+                .write(econ_item_view + initialized, 1u8);
         }
         
         true
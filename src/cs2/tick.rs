@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+use crate::cs2::global_vars::TickState;
+
+/// Loop interval when tick timing is unknown.
+const FIXED_INTERVAL: Duration = Duration::from_millis(10);
+/// Poll interval once the next tick is due.
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+/// Tick interval until one is measured, 64 tick.
+const DEFAULT_TICK_INTERVAL: Duration = Duration::from_micros(15_625);
+/// Measured intervals are kept between 128 and 8 tick, anything outside is a
+/// hitch rather than the tick rate.
+const MIN_TICK_INTERVAL: Duration = Duration::from_micros(7_812);
+const MAX_TICK_INTERVAL: Duration = Duration::from_millis(125);
+/// Wake this long before the predicted tick, so it is caught right away.
+const EARLY: Duration = Duration::from_millis(2);
+/// Without a new tick for this long the game counts as idle.
+const IDLE_AFTER: Duration = Duration::from_millis(250);
+const MAX_IDLE_SLEEP: Duration = Duration::from_millis(500);
+
+/// What the main loop should do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wake {
+    pub run: bool,
+    pub sleep: Duration,
+}
+
+/// Lines skin application up with game ticks.
+///
+/// The game resets econ item fields while processing a network update, which
+/// happens when the tick advances. Running once right after each tick change
+/// reapplies skins at exactly those points, instead of every 10 ms whether the
+/// game did anything or not. The loop sleeps until shortly before the next
+/// tick, predicted from the measured tick interval, and only polls once it is
+/// due. While ticks stop (menu, loading, paused) the sleep backs off up to
+/// [`MAX_IDLE_SLEEP`] between runs.
+#[derive(Debug)]
+pub struct TickScheduler {
+    last: Option<(i32, i32)>,
+    last_change: Instant,
+    tick_interval: Duration,
+    idle_sleep: Duration,
+}
+
+impl TickScheduler {
+    pub fn new() -> Self {
+        Self {
+            last: None,
+            last_change: Instant::now(),
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            idle_sleep: DEFAULT_TICK_INTERVAL,
+        }
+    }
+
+    pub fn update(&mut self, state: TickState, now: Instant) -> Wake {
+        let (tick, frame) = match state {
+            TickState::Unknown => {
+                return Wake {
+                    run: true,
+                    sleep: FIXED_INTERVAL,
+                };
+            }
+            TickState::Stopped => {
+                self.last = None;
+                return self.idle();
+            }
+            TickState::Running { tick, frame } => (tick, frame),
+        };
+
+        let since_change = now.saturating_duration_since(self.last_change);
+        let last = self.last.replace((tick, frame));
+        match last {
+            Some((last_tick, _)) if last_tick == tick => {
+                let next_tick = self.tick_interval;
+                if since_change < next_tick {
+                    // not due yet
+                    Wake {
+                        run: false,
+                        sleep: (next_tick - since_change).max(POLL_INTERVAL),
+                    }
+                } else if since_change < next_tick * 2 {
+                    Wake {
+                        run: false,
+                        sleep: POLL_INTERVAL,
+                    }
+                } else if since_change < IDLE_AFTER {
+                    // a tick was missed, check once per tick until idle
+                    Wake {
+                        run: false,
+                        sleep: next_tick,
+                    }
+                } else {
+                    self.idle()
+                }
+            }
+            _ => {
+                if let Some((last_tick, _)) = last {
+                    self.measure(tick.wrapping_sub(last_tick), since_change);
+                }
+                self.last_change = now;
+                self.idle_sleep = self.tick_interval;
+                Wake {
+                    run: true,
+                    sleep: self.tick_interval.saturating_sub(EARLY),
+                }
+            }
+        }
+    }
+
+    /// Folds the time `ticks` ticks took into the tick interval.
+    fn measure(&mut self, ticks: i32, elapsed: Duration) {
+        let Ok(ticks @ 1..=8) = u32::try_from(ticks) else {
+            return;
+        };
+        let sample = (elapsed / ticks).clamp(MIN_TICK_INTERVAL, MAX_TICK_INTERVAL);
+        self.tick_interval = (self.tick_interval * 3 + sample) / 4;
+    }
+
+    /// Idle wakes still run, so the game state keeps up with the menu and loading screens.
+    fn idle(&mut self) -> Wake {
+        self.idle_sleep = (self.idle_sleep * 2).min(MAX_IDLE_SLEEP);
+        Wake {
+            run: true,
+            sleep: self.idle_sleep,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK_128: Duration = Duration::from_micros(7_812);
+
+    fn running(tick: i32) -> TickState {
+        TickState::Running { tick, frame: tick }
+    }
+
+    /// Runs the scheduler against a game ticking every `interval` for
+    /// `duration`, sleeping as told. Returns the wakes and the ticks run on.
+    fn simulate(interval: Duration, duration: Duration) -> (TickScheduler, usize, usize) {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new();
+        let mut elapsed = Duration::ZERO;
+        let (mut wakes, mut runs) = (0, 0);
+        while elapsed < duration {
+            let tick = (elapsed.as_micros() / interval.as_micros()) as i32;
+            let wake = scheduler.update(running(tick), start + elapsed);
+            wakes += 1;
+            runs += usize::from(wake.run);
+            elapsed += wake.sleep;
+        }
+        (scheduler, wakes, runs)
+    }
+
+    #[test]
+    fn learns_the_tick_interval() {
+        let (scheduler, wakes, runs) = simulate(TICK_128, Duration::from_secs(2));
+        let error = scheduler.tick_interval.abs_diff(TICK_128);
+        assert!(
+            error < Duration::from_micros(500),
+            "{:?}",
+            scheduler.tick_interval
+        );
+        // every tick is caught, with a few polls each instead of one per millisecond
+        assert!(runs >= 250, "{runs}");
+        assert!(wakes < 4 * runs, "{wakes} wakes for {runs} runs");
+    }
+
+    #[test]
+    fn sleeps_until_the_next_tick() {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new();
+        assert!(scheduler.update(running(1), start).run);
+        let wake = scheduler.update(running(1), start + Duration::from_millis(5));
+        assert_eq!(
+            wake,
+            Wake {
+                run: false,
+                sleep: DEFAULT_TICK_INTERVAL - Duration::from_millis(5),
+            }
+        );
+        let due = start + DEFAULT_TICK_INTERVAL + Duration::from_millis(1);
+        assert_eq!(scheduler.update(running(1), due).sleep, POLL_INTERVAL);
+    }
+
+    #[test]
+    fn backs_off_while_ticks_stop() {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new();
+        scheduler.update(running(1), start);
+        let mut elapsed = Duration::ZERO;
+        let mut wakes = 0;
+        while elapsed < Duration::from_secs(5) {
+            elapsed += scheduler.update(running(1), start + elapsed).sleep;
+            wakes += 1;
+        }
+        assert!(wakes < 50, "{wakes} wakes in 5 s");
+        assert_eq!(
+            scheduler.update(running(1), start + elapsed).sleep,
+            MAX_IDLE_SLEEP
+        );
+
+        let stopped = scheduler.update(TickState::Stopped, start + elapsed);
+        assert!(stopped.run);
+        assert_eq!(stopped.sleep, MAX_IDLE_SLEEP);
+    }
+
+    #[test]
+    fn unknown_timing_runs_on_a_fixed_interval() {
+        let mut scheduler = TickScheduler::new();
+        let wake = scheduler.update(TickState::Unknown, Instant::now());
+        assert_eq!(
+            wake,
+            Wake {
+                run: true,
+                sleep: FIXED_INTERVAL,
+            }
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use crate::{constants::cs2, game_data::vdf::Vdf, os::process::Process};
+
+pub const APP_ID: u32 = 730;
+
+/// Where an install was found.
+#[derive(Debug, Clone)]
+pub enum InstallSource {
+    /// Steam library folder.
+    Library(PathBuf),
+    /// Running game process.
+    Process(i32),
+}
+
+impl Display for InstallSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallSource::Library(path) => write!(f, "Steam library {path:?}"),
+            InstallSource::Process(pid) => write!(f, "running process {pid}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Install {
+    /// `game/csgo`, the directory holding `pak01_dir.vpk`.
+    pub content_dir: PathBuf,
+    /// Steam build ID from `appmanifest_730.acf`.
+    pub build_id: Option<u32>,
+    pub source: InstallSource,
+}
+
+/// Finds CS2 through the Steam libraries, then through the running game.
+pub fn discover() -> Option<Install> {
+    for root in steam_roots() {
+        for library in library_folders(&root) {
+            if let Some(install) = from_library(&library) {
+                return Some(install);
+            }
+        }
+    }
+    let pid = Process::get_pid(cs2::PROCESS_NAME)?;
+    from_process(pid)
+}
+
+/// Native and flatpak Steam roots that exist, without duplicates through symlinks.
+fn steam_roots() -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let flatpak = home.join(".var/app/com.valvesoftware.Steam");
+    let candidates = [
+        home.join(".steam/steam"),
+        home.join(".steam/root"),
+        home.join(".local/share/Steam"),
+        flatpak.join(".local/share/Steam"),
+        flatpak.join("data/Steam"),
+    ];
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if let Ok(root) = candidate.canonicalize()
+            && root.join("steamapps").is_dir()
+            && !roots.contains(&root)
+        {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Library folders listed in `steamapps/libraryfolders.vdf`, the root first.
+fn library_folders(root: &Path) -> Vec<PathBuf> {
+    let mut libraries = vec![root.to_path_buf()];
+    let Ok(source) = std::fs::read_to_string(root.join("steamapps/libraryfolders.vdf")) else {
+        return libraries;
+    };
+    let Ok(vdf) = Vdf::parse(&source) else {
+        log::warn!("could not parse libraryfolders.vdf in {root:?}");
+        return libraries;
+    };
+    let folders = vdf
+        .get("libraryfolders")
+        .map(Vdf::entries)
+        .unwrap_or_default();
+    for (_, folder) in folders {
+        // current format nests the path, older files map the index to it
+        let path = folder.get_str("path").or(folder.as_str());
+        if let Some(path) = path.map(PathBuf::from)
+            && !libraries.contains(&path)
+        {
+            libraries.push(path);
+        }
+    }
+    libraries
+}
+
+fn from_library(library: &Path) -> Option<Install> {
+    let steamapps = library.join("steamapps");
+    let manifest = read_manifest(&steamapps)?;
+    let install_dir = manifest.get_str("installdir")?;
+    let content_dir = steamapps.join("common").join(install_dir).join("game/csgo");
+    content_dir.is_dir().then(|| Install {
+        content_dir,
+        build_id: build_id(&manifest),
+        source: InstallSource::Library(library.to_path_buf()),
+    })
+}
+
+/// `AppState` of `steamapps/appmanifest_730.acf`.
+fn read_manifest(steamapps: &Path) -> Option<Vdf> {
+    let source =
+        std::fs::read_to_string(steamapps.join(format!("appmanifest_{APP_ID}.acf"))).ok()?;
+    Vdf::parse(&source).ok()?.get("AppState").cloned()
+}
+
+fn build_id(manifest: &Vdf) -> Option<u32> {
+    manifest.get_str("buildid")?.parse().ok()
+}
+
+/// `game/bin/linuxsteamrt64/cs2` -> `game/csgo`, from the exe or else the
+/// working directory of the game.
+pub fn from_process(pid: i32) -> Option<Install> {
+    let exe = std::fs::read_link(format!("/proc/{pid}/exe")).ok();
+    let cwd = std::fs::read_link(format!("/proc/{pid}/cwd")).ok();
+    let content_dir = [exe.as_deref().and_then(Path::parent), cwd.as_deref()]
+        .into_iter()
+        .flatten()
+        .flat_map(Path::ancestors)
+        .map(|dir| dir.join("csgo"))
+        .find(|dir| dir.join("pak01_dir.vpk").is_file())?;
+
+    // steamapps/common/<installdir>/game/csgo
+    let build_id = content_dir
+        .ancestors()
+        .nth(4)
+        .and_then(read_manifest)
+        .as_ref()
+        .and_then(build_id);
+    Some(Install {
+        content_dir,
+        build_id,
+        source: InstallSource::Process(pid),
+    })
+}
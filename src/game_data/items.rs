@@ -0,0 +1,427 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+
+use crate::{
+    cs2::entity::weapon::Weapon,
+    game_data::vdf::{Vdf, VdfError},
+};
+
+/// Prefab chains deeper than this are treated as broken.
+const MAX_PREFAB_DEPTH: usize = 16;
+
+/// Wear range of paint kits that do not set one, and of paint kit 0.
+const DEFAULT_FLOAT_RANGE: (f32, f32) = (0.06, 0.8);
+
+const ICON_PREFIX: &str = "econ/default_generated/";
+const ICON_SUFFIX: &str = "_light";
+
+/// An entry of the `items` section with its prefabs applied.
+#[derive(Debug, Clone)]
+pub struct ItemDefinition {
+    pub index: u32,
+    /// `weapon_ak47`
+    pub name: String,
+    /// Localization token, `#SFUI_WPNHUD_AK47`
+    pub item_name: Option<String>,
+    /// Every prefab the item inherits from, nearest first.
+    pub prefabs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PaintKit {
+    pub id: u32,
+    /// `cu_ak47_asiimov`
+    pub name: String,
+    /// Localization token of the display name, `#PaintKit_cu_ak47_asiimov_Tag`
+    pub description_tag: Option<String>,
+    pub min_float: f32,
+    pub max_float: f32,
+    pub rarity: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rarity {
+    pub name: String,
+    /// Tier, 0 is default and higher is rarer.
+    pub value: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct StickerKit {
+    pub id: u32,
+    pub name: String,
+    pub item_name: Option<String>,
+}
+
+/// Item definitions, paint kits, rarities, qualities and sticker kits from
+/// `items_game.txt`.
+#[derive(Debug, Default)]
+pub struct ItemDatabase {
+    items: BTreeMap<u32, ItemDefinition>,
+    item_indices: HashMap<String, u32>,
+    paint_kits: BTreeMap<u32, PaintKit>,
+    rarities: HashMap<String, Rarity>,
+    /// Quality value -> name, which is also its localization token.
+    qualities: BTreeMap<u32, String>,
+    sticker_kits: BTreeMap<u32, StickerKit>,
+    /// Paint kits the game ships for each item name, taken from the
+    /// generated weapon icons.
+    weapon_paint_kits: HashMap<String, BTreeSet<u32>>,
+}
+
+impl ItemDatabase {
+    pub fn parse(source: &str) -> Result<Self, VdfError> {
+        let root = Vdf::parse(source)?;
+        // the root key is "items_game", accept a bare section list too
+        let root = root.get("items_game").unwrap_or(&root);
+        Ok(Self::from_vdf(root))
+    }
+
+    pub fn from_vdf(root: &Vdf) -> Self {
+        let mut database = Self::default();
+
+        let prefabs: HashMap<String, &Vdf> = root
+            .get_all("prefabs")
+            .flat_map(Vdf::entries)
+            .map(|(name, prefab)| (name.to_ascii_lowercase(), prefab))
+            .collect();
+
+        for (index, item) in root.get_all("items").flat_map(Vdf::entries) {
+            let Ok(index) = index.parse::<u32>() else {
+                continue;
+            };
+            let Some(name) = item.get_str("name") else {
+                continue;
+            };
+            database.item_indices.insert(name.to_string(), index);
+            let chain = prefab_chain(item, &prefabs);
+            let item_name = std::iter::once(item)
+                .chain(
+                    chain
+                        .iter()
+                        .filter_map(|prefab| prefabs.get(prefab).copied()),
+                )
+                .find_map(|node| node.get_str("item_name"))
+                .map(str::to_string);
+            database.items.insert(
+                index,
+                ItemDefinition {
+                    index,
+                    name: name.to_string(),
+                    item_name,
+                    prefabs: chain,
+                },
+            );
+        }
+
+        for (name, rarity) in root.get_all("rarities").flat_map(Vdf::entries) {
+            database.rarities.insert(
+                name.to_ascii_lowercase(),
+                Rarity {
+                    name: name.clone(),
+                    value: rarity
+                        .get_str("value")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or_default(),
+                },
+            );
+        }
+
+        for (name, quality) in root.get_all("qualities").flat_map(Vdf::entries) {
+            if let Some(value) = quality.get_str("value").and_then(|v| v.parse().ok()) {
+                database.qualities.insert(value, name.clone());
+            }
+        }
+
+        let kit_rarities: HashMap<String, &str> = root
+            .get_all("paint_kits_rarity")
+            .flat_map(Vdf::entries)
+            .filter_map(|(kit, rarity)| Some((kit.to_ascii_lowercase(), rarity.as_str()?)))
+            .collect();
+        let kits: Vec<(u32, &Vdf)> = root
+            .get_all("paint_kits")
+            .flat_map(Vdf::entries)
+            .filter_map(|(id, kit)| Some((id.parse().ok()?, kit)))
+            .collect();
+        let float = |kit: &Vdf, key: &str| {
+            kit.get_str(key)
+                .and_then(|v| v.parse::<f32>().ok())
+                .filter(|v| v.is_finite())
+        };
+        // an inverted range falls back to the default one
+        let float_range = |kit: Option<&Vdf>, (default_min, default_max)| {
+            let min = kit.and_then(|kit| float(kit, "wear_remap_min"));
+            let max = kit.and_then(|kit| float(kit, "wear_remap_max"));
+            let range = (min.unwrap_or(default_min), max.unwrap_or(default_max));
+            if range.0 <= range.1 {
+                range
+            } else {
+                (default_min, default_max)
+            }
+        };
+        let default_kit = kits.iter().find(|(id, _)| *id == 0).map(|(_, kit)| *kit);
+        let default_range = float_range(default_kit, DEFAULT_FLOAT_RANGE);
+        for (id, kit) in kits {
+            let Some(name) = kit.get_str("name") else {
+                continue;
+            };
+            let (min_float, max_float) = float_range(Some(kit), default_range);
+            database.paint_kits.insert(
+                id,
+                PaintKit {
+                    id,
+                    name: name.to_string(),
+                    description_tag: kit.get_str("description_tag").map(str::to_string),
+                    min_float,
+                    max_float,
+                    rarity: kit_rarities
+                        .get(&name.to_ascii_lowercase())
+                        .map(|rarity| rarity.to_string()),
+                },
+            );
+        }
+
+        for (id, kit) in root.get_all("sticker_kits").flat_map(Vdf::entries) {
+            let (Ok(id), Some(name)) = (id.parse::<u32>(), kit.get_str("name")) else {
+                continue;
+            };
+            database.sticker_kits.insert(
+                id,
+                StickerKit {
+                    id,
+                    name: name.to_string(),
+                    item_name: kit.get_str("item_name").map(str::to_string),
+                },
+            );
+        }
+
+        database.weapon_paint_kits = database.icon_paint_kits(root);
+        database
+    }
+
+    /// `econ/default_generated/weapon_ak47_cu_ak47_asiimov_light` is the
+    /// icon of paint kit `cu_ak47_asiimov` on `weapon_ak47`. Both names
+    /// contain underscores, so every split point is tried.
+    fn icon_paint_kits(&self, root: &Vdf) -> HashMap<String, BTreeSet<u32>> {
+        let item_names: BTreeSet<&str> =
+            self.items.values().map(|item| item.name.as_str()).collect();
+        let kit_ids: HashMap<&str, u32> = self
+            .paint_kits
+            .values()
+            .map(|kit| (kit.name.as_str(), kit.id))
+            .collect();
+
+        let mut weapon_paint_kits: HashMap<String, BTreeSet<u32>> = HashMap::new();
+        let icons = root
+            .get_all("alternate_icons2")
+            .flat_map(|icons| icons.get_all("weapon_icons"))
+            .flat_map(Vdf::entries);
+        for (_, icon) in icons {
+            let Some(path) = icon
+                .get_str("icon_path")
+                .and_then(|path| path.strip_prefix(ICON_PREFIX))
+                .and_then(|path| path.strip_suffix(ICON_SUFFIX))
+            else {
+                continue;
+            };
+            let split = path.match_indices('_').find_map(|(at, _)| {
+                let (item, kit) = (&path[..at], &path[at + 1..]);
+                Some((item, *kit_ids.get(kit)?)).filter(|(item, _)| item_names.contains(item))
+            });
+            if let Some((item, kit)) = split {
+                weapon_paint_kits
+                    .entry(item.to_string())
+                    .or_default()
+                    .insert(kit);
+            }
+        }
+        weapon_paint_kits
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = &ItemDefinition> {
+        self.items.values()
+    }
+
+    pub fn item(&self, index: u32) -> Option<&ItemDefinition> {
+        self.items.get(&index)
+    }
+
+    /// Item definition called `name`, `weapon_ak47`.
+    pub fn item_by_name(&self, name: &str) -> Option<&ItemDefinition> {
+        self.item(*self.item_indices.get(name)?)
+    }
+
+    pub fn paint_kits(&self) -> impl Iterator<Item = &PaintKit> {
+        self.paint_kits.values()
+    }
+
+    pub fn paint_kit(&self, id: u32) -> Option<&PaintKit> {
+        self.paint_kits.get(&id)
+    }
+
+    pub fn rarity(&self, name: &str) -> Option<&Rarity> {
+        self.rarities.get(&name.to_ascii_lowercase())
+    }
+
+    pub fn quality(&self, value: u32) -> Option<&str> {
+        self.qualities.get(&value).map(String::as_str)
+    }
+
+    pub fn sticker_kits(&self) -> impl Iterator<Item = &StickerKit> {
+        self.sticker_kits.values()
+    }
+
+    pub fn sticker_kit(&self, id: u32) -> Option<&StickerKit> {
+        self.sticker_kits.get(&id)
+    }
+
+    /// Paint kits shipped for the item called `item_name`, `None` when no
+    /// icon names it.
+    pub fn weapon_paint_kits(&self, item_name: &str) -> Option<&BTreeSet<u32>> {
+        self.weapon_paint_kits.get(item_name)
+    }
+
+    /// Paint kits shipped for any item of `weapon`, `None` when no icon
+    /// names one.
+    pub fn valid_paint_kits(&self, weapon: &Weapon) -> Option<BTreeSet<u32>> {
+        let kits: BTreeSet<u32> = self
+            .weapon_paint_kits
+            .iter()
+            .filter(|(item, _)| {
+                let item = Weapon::from_item_name(item);
+                item == *weapon || (*weapon == Weapon::Knife && item.is_knife())
+            })
+            .flat_map(|(_, kits)| kits.iter().copied())
+            .collect();
+        (!kits.is_empty()).then_some(kits)
+    }
+
+    /// Weapon for an item definition index.
+    pub fn weapon(&self, index: u32) -> Weapon {
+        self.item(index)
+            .map(|item| Weapon::from_item_name(&item.name))
+            .unwrap_or_default()
+    }
+}
+
+/// Prefabs `node` inherits from, nearest first. `prefab` may list several
+/// space separated names.
+fn prefab_chain(node: &Vdf, prefabs: &HashMap<String, &Vdf>) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut pending: VecDeque<String> = parents(node).into();
+    while let Some(name) = pending.pop_front() {
+        if chain.contains(&name) || chain.len() >= MAX_PREFAB_DEPTH {
+            continue;
+        }
+        if let Some(prefab) = prefabs.get(&name) {
+            pending.extend(parents(prefab));
+        }
+        chain.push(name);
+    }
+    chain
+}
+
+fn parents(node: &Vdf) -> Vec<String> {
+    node.get_str("prefab")
+        .map(|prefab| {
+            prefab
+                .split_whitespace()
+                .map(str::to_ascii_lowercase)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items() -> ItemDatabase {
+        ItemDatabase::parse(include_str!("testdata/items_game.txt")).unwrap()
+    }
+
+    #[test]
+    fn applies_prefabs() {
+        let items = items();
+        let ak47 = items.item_by_name("weapon_ak47").unwrap();
+        assert_eq!(ak47.index, 7);
+        assert_eq!(ak47.prefabs, ["ak47_prefab", "primary", "weapon_base"]);
+        // the nearest prefab setting it wins
+        assert_eq!(ak47.item_name.as_deref(), Some("#SFUI_WPNHUD_AK47"));
+        let karambit = items.item(507).unwrap();
+        assert_eq!(karambit.prefabs, ["melee_unusual", "melee", "weapon_base"]);
+        assert_eq!(karambit.item_name.as_deref(), Some("#base"));
+    }
+
+    #[test]
+    fn stops_broken_prefab_chains() {
+        let items = items();
+        assert_eq!(items.item(9).unwrap().prefabs, ["loop_a", "loop_b"]);
+        let deep = &items.item(1).unwrap().prefabs;
+        assert_eq!(deep.len(), MAX_PREFAB_DEPTH);
+        assert_eq!(deep.last().unwrap(), &format!("deep_{MAX_PREFAB_DEPTH}"));
+    }
+
+    #[test]
+    fn reads_every_section() {
+        let items = items();
+        // conditional items and repeated sections
+        assert_eq!(items.weapon(525), Weapon::SkeletonKnife);
+        assert_eq!(items.weapon(508), Weapon::M9Bayonet);
+        assert_eq!(items.weapon(1234), Weapon::Unknown);
+        assert_eq!(items.quality(9), Some("strange"));
+        assert_eq!(items.rarity("Ancient").unwrap().value, 6);
+
+        let asiimov = items.paint_kit(279).unwrap();
+        assert_eq!((asiimov.min_float, asiimov.max_float), (0.18, 1.0));
+        assert_eq!(asiimov.rarity.as_deref(), Some("ancient"));
+        // paint kits without a range use the default paint kit's
+        let doppler = items.paint_kit(418).unwrap();
+        assert_eq!((doppler.min_float, doppler.max_float), (0.06, 0.8));
+    }
+
+    #[test]
+    fn replaces_broken_float_ranges() {
+        let items = ItemDatabase::parse(
+            r#"
+            "paint_kits"
+            {
+                "0" { "name" "default" "wear_remap_min" "0.9" "wear_remap_max" "0.1" }
+                "1" { "name" "inverted" "wear_remap_min" "0.8" "wear_remap_max" "0.2" }
+                "2" { "name" "not_a_number" "wear_remap_min" "nan" "wear_remap_max" "0.5" }
+                "3" { "name" "infinite" "wear_remap_min" "0.1" "wear_remap_max" "inf" }
+            }
+            "#,
+        )
+        .unwrap();
+        let range = |id| {
+            let kit = items.paint_kit(id).unwrap();
+            (kit.min_float, kit.max_float)
+        };
+        assert_eq!(range(0), DEFAULT_FLOAT_RANGE);
+        assert_eq!(range(1), DEFAULT_FLOAT_RANGE);
+        assert_eq!(range(2), (0.06, 0.5));
+        assert_eq!(range(3), (0.1, 0.8));
+    }
+
+    #[test]
+    fn matches_icons_to_items_and_paint_kits() {
+        let items = items();
+        let kits = |item| items.weapon_paint_kits(item).cloned().unwrap_or_default();
+        // only `_light` icons, split where both names exist
+        assert_eq!(kits("weapon_ak47"), BTreeSet::from([44, 279]));
+        assert_eq!(
+            kits("weapon_knife_karambit"),
+            BTreeSet::from([38, 44, 418, 419])
+        );
+        assert_eq!(kits("weapon_knife_m9_bayonet"), BTreeSet::from([38]));
+        assert!(items.weapon_paint_kits("weapon_knife").is_none());
+        assert!(items.weapon_paint_kits("weapon_awp").is_none());
+
+        // the knife entry takes the paint kits of every knife model
+        assert_eq!(
+            items.valid_paint_kits(&Weapon::Knife),
+            Some(BTreeSet::from([38, 44, 418, 419]))
+        );
+        assert_eq!(items.valid_paint_kits(&Weapon::Deagle), None);
+    }
+}
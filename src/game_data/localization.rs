@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::game_data::vdf::{Vdf, VdfError};
+
+/// Tokens of one `csgo_<language>.txt` file.
+#[derive(Debug, Default)]
+pub struct Localization {
+    language: String,
+    /// Lowercase token without `#` -> text
+    tokens: HashMap<String, String>,
+}
+
+impl Localization {
+    /// Parses a `"lang" { "Language" ... "Tokens" { ... } }` file, UTF-8 or
+    /// UTF-16 with a byte order mark.
+    pub fn parse(data: &[u8]) -> Result<Self, VdfError> {
+        let root = Vdf::parse(&decode(data))?;
+        let lang = root.get("lang").unwrap_or(&root);
+        let mut localization = Self {
+            language: lang
+                .get_str("Language")
+                .unwrap_or_default()
+                .to_ascii_lowercase(),
+            tokens: HashMap::new(),
+        };
+        for (token, text) in lang.get_all("Tokens").flat_map(Vdf::entries) {
+            // translated files keep the English source as "[english]Token"
+            if token.starts_with('[') {
+                continue;
+            }
+            if let Some(text) = text.as_str() {
+                localization
+                    .tokens
+                    .insert(token.to_ascii_lowercase(), text.to_string());
+            }
+        }
+        Ok(localization)
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Text of `token`, with or without the leading `#`.
+    pub fn get(&self, token: &str) -> Option<&str> {
+        let token = token.strip_prefix('#').unwrap_or(token);
+        self.tokens
+            .get(&token.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    /// Adds the tokens of `fallback` that this file does not translate.
+    pub fn fill_from(&mut self, fallback: Localization) {
+        for (token, text) in fallback.tokens {
+            self.tokens.entry(token).or_insert(text);
+        }
+    }
+}
+
+/// Localization files were UTF-16LE in CS:GO and are UTF-8 in CS2.
+fn decode(data: &[u8]) -> String {
+    match data {
+        [0xFF, 0xFE, rest @ ..] => {
+            let units: Vec<u16> = rest
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        _ => String::from_utf8_lossy(data).into_owned(),
+    }
+}
@@ -0,0 +1,279 @@
+use std::fmt::Display;
+
+use crate::{
+    cs2::entity::weapon::Weapon,
+    game_data::{
+        GameData,
+        items::{ItemDatabase, PaintKit},
+        localization::Localization,
+    },
+};
+
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug)]
+pub enum LookupError {
+    NoDatabase,
+    Unknown {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    NotForWeapon {
+        name: String,
+        weapon: String,
+    },
+    /// `AK-47 | Asiimov` in the entry of another weapon.
+    WrongWeapon {
+        named: String,
+        weapon: String,
+    },
+    Ambiguous {
+        name: String,
+        ids: Vec<u32>,
+    },
+}
+
+impl Display for LookupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LookupError::NoDatabase => {
+                write!(f, "paint kit names need the item database, use the ID")
+            }
+            LookupError::Unknown { name, suggestions } if suggestions.is_empty() => {
+                write!(f, "unknown paint kit {name:?}")
+            }
+            LookupError::Unknown { name, suggestions } => {
+                let suggestions: Vec<String> =
+                    suggestions.iter().map(|s| format!("{s:?}")).collect();
+                write!(
+                    f,
+                    "unknown paint kit {name:?}, did you mean {}?",
+                    suggestions.join(" or ")
+                )
+            }
+            LookupError::NotForWeapon { name, weapon } => {
+                write!(f, "{name:?} is not available for the {weapon}")
+            }
+            LookupError::WrongWeapon { named, weapon } => {
+                write!(f, "names the {named}, but this entry is for the {weapon}")
+            }
+            LookupError::Ambiguous { name, ids } => {
+                let ids: Vec<String> = ids.iter().map(u32::to_string).collect();
+                write!(
+                    f,
+                    "{name:?} matches paint kits {}, use the ID",
+                    ids.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Display name of a paint kit: localized, or its internal name.
+fn display_name<'a>(kit: &'a PaintKit, localization: Option<&'a Localization>) -> &'a str {
+    kit.description_tag
+        .as_deref()
+        .and_then(|tag| localization?.get(tag))
+        .unwrap_or(&kit.name)
+}
+
+/// Resolves `AK-47 | Fire Serpent`, `Fire Serpent` or the internal
+/// `cu_fireserpent_ak47` to a paint kit available for `weapon`. Names are
+/// case-insensitive.
+pub fn find_paint_kit(
+    game_data: &GameData,
+    weapon: &Weapon,
+    name: &str,
+) -> Result<u32, LookupError> {
+    let items = game_data.items().ok_or(LookupError::NoDatabase)?;
+    find_paint_kit_in(items, game_data.localization(), weapon, name)
+}
+
+fn find_paint_kit_in(
+    items: &ItemDatabase,
+    localization: Option<&Localization>,
+    weapon: &Weapon,
+    name: &str,
+) -> Result<u32, LookupError> {
+    let display_name = |kit| display_name(kit, localization);
+    let (named_weapon, kit_name) = match name.split_once('|') {
+        Some((weapon, kit)) => (Some(weapon.trim()), kit.trim()),
+        None => (None, name.trim()),
+    };
+    let weapon_name = weapon.to_string();
+    if let Some(named) = named_weapon
+        && !named.eq_ignore_ascii_case(&weapon_name)
+        && !named.eq_ignore_ascii_case(weapon.english_name())
+    {
+        return Err(LookupError::WrongWeapon {
+            named: named.to_string(),
+            weapon: weapon_name,
+        });
+    }
+
+    let wanted = kit_name.to_lowercase();
+    let matches: Vec<u32> = items
+        .paint_kits()
+        .filter(|kit| kit.id != 0)
+        .filter(|kit| {
+            kit.name.to_lowercase() == wanted || display_name(kit).to_lowercase() == wanted
+        })
+        .map(|kit| kit.id)
+        .collect();
+    // without icons for the weapon every paint kit is allowed
+    let valid = items.valid_paint_kits(weapon);
+    let available: Vec<u32> = matches
+        .iter()
+        .copied()
+        .filter(|id| valid.as_ref().is_none_or(|valid| valid.contains(id)))
+        .collect();
+
+    match available.as_slice() {
+        [id] => Ok(*id),
+        [] if !matches.is_empty() => Err(LookupError::NotForWeapon {
+            name: kit_name.to_string(),
+            weapon: weapon_name,
+        }),
+        [] => {
+            // prefer names the weapon has, then any paint kit
+            let mut suggestions = suggest(
+                &wanted,
+                items
+                    .paint_kits()
+                    .filter(|kit| valid.as_ref().is_some_and(|valid| valid.contains(&kit.id)))
+                    .map(display_name),
+            );
+            if suggestions.is_empty() {
+                suggestions = suggest(&wanted, items.paint_kits().map(display_name));
+            }
+            Err(LookupError::Unknown {
+                name: kit_name.to_string(),
+                suggestions,
+            })
+        }
+        ids => Err(LookupError::Ambiguous {
+            name: kit_name.to_string(),
+            ids: ids.to_vec(),
+        }),
+    }
+}
+
+/// Whether paint kit `id` is available for `weapon`, `None` when unknown.
+pub fn paint_kit_available(game_data: &GameData, weapon: &Weapon, id: u32) -> Option<bool> {
+    let valid = game_data.items()?.valid_paint_kits(weapon)?;
+    Some(valid.contains(&id))
+}
+
+/// Closest names to `wanted` within a third of its length.
+pub fn suggest<'a>(wanted: &str, names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let limit = (wanted.chars().count() / 3).max(2);
+    let mut close: Vec<(usize, &str)> = names
+        .map(|name| (levenshtein(wanted, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    close.sort();
+    close.dedup_by(|a, b| a.1 == b.1);
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Edit distance counting insertions, deletions and substitutions.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(weapon: Weapon, name: &str) -> Result<u32, LookupError> {
+        let items = ItemDatabase::parse(include_str!("testdata/items_game.txt")).unwrap();
+        let localization =
+            Localization::parse(include_bytes!("testdata/csgo_english.txt")).unwrap();
+        find_paint_kit_in(&items, Some(&localization), &weapon, name)
+    }
+
+    #[test]
+    fn finds_paint_kits_by_name() {
+        for name in ["Asiimov", "asiimov", "AK-47 | Asiimov", "cu_ak47_asiimov"] {
+            assert_eq!(find(Weapon::Ak47, name).unwrap(), 279, "{name}");
+        }
+        assert_eq!(find(Weapon::Karambit, "am_doppler_phase2").unwrap(), 419);
+        // no icons for the weapon, every paint kit is allowed
+        assert_eq!(find(Weapon::Deagle, "Fade").unwrap(), 38);
+    }
+
+    #[test]
+    fn rejects_names_for_other_weapons() {
+        assert!(matches!(
+            find(Weapon::Ak47, "M4A4 | Asiimov"),
+            Err(LookupError::WrongWeapon { named, .. }) if named == "M4A4"
+        ));
+        assert!(matches!(
+            find(Weapon::Karambit, "Asiimov"),
+            Err(LookupError::NotForWeapon { .. })
+        ));
+    }
+
+    #[test]
+    fn shared_names_are_ambiguous() {
+        assert!(matches!(
+            find(Weapon::Karambit, "Doppler"),
+            Err(LookupError::Ambiguous { ids, .. }) if ids == [418, 419]
+        ));
+    }
+
+    #[test]
+    fn suggests_close_names() {
+        let suggestions = |weapon, name| match find(weapon, name) {
+            Err(LookupError::Unknown { suggestions, .. }) => suggestions,
+            other => panic!("{name}: {other:?}"),
+        };
+        assert_eq!(suggestions(Weapon::Ak47, "Asimov"), ["Asiimov"]);
+        // names the weapon has come first
+        assert_eq!(
+            suggestions(Weapon::Karambit, "Case Hardend"),
+            ["Case Hardened"]
+        );
+        assert_eq!(suggestions(Weapon::Karambit, "Asiimow"), ["Asiimov"]);
+        // more than a third of the name away
+        assert!(suggestions(Weapon::Ak47, "Redline").is_empty());
+        assert!(suggestions(Weapon::Ak47, "Xyz").is_empty());
+    }
+
+    #[test]
+    fn suggestions_are_limited_and_sorted() {
+        let names = ["Fade", "Fades", "Fade", "Made", "Faded", "Jade", "Blaze"];
+        let suggestions = suggest("fade", names.into_iter());
+        // closest first, ties by name, duplicates once
+        assert_eq!(suggestions, ["Fade", "Faded", "Fades"]);
+        assert_eq!(
+            suggest("fade", ["Marble Fade"].into_iter()),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn edit_distance() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("asiimov", "asiimov"), 0);
+        assert_eq!(levenshtein("fünf", "funf"), 1);
+        assert_eq!(levenshtein("ab", "ba"), 2);
+    }
+}
@@ -0,0 +1,275 @@
+pub mod install;
+pub mod items;
+pub mod localization;
+pub mod lookup;
+pub mod vdf;
+pub mod vpk;
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, LazyLock, OnceLock, RwLock},
+};
+
+use crate::{
+    config::BASE_PATH,
+    cs2::entity::weapon::Weapon,
+    game_data::{install::Install, items::ItemDatabase, localization::Localization, vpk::Vpk},
+};
+
+/// Path of the item schema inside the game's VPK. An extracted copy with the
+/// same file name in `BASE_PATH` takes precedence.
+pub const ITEMS_GAME_PATH: &str = "scripts/items/items_game.txt";
+
+/// Directory file of the game's main archive, in the `game/csgo` directory.
+pub const PAK_NAME: &str = "pak01_dir.vpk";
+
+pub const DEFAULT_LANGUAGE: &str = "english";
+
+/// Quality of StatTrak items.
+pub const STATTRAK_QUALITY: u32 = 9;
+
+static INSTALL: LazyLock<Option<Install>> = LazyLock::new(|| {
+    let install = install::discover();
+    match &install {
+        Some(install) => log::info!(
+            "found CS2 in {:?} through {}",
+            install.content_dir,
+            install.source
+        ),
+        None => log::info!("CS2 install not found, set game_dir in the config"),
+    }
+    install
+});
+
+/// The discovered install, searched on first use.
+pub fn install() -> Option<&'static Install> {
+    INSTALL.as_ref()
+}
+
+/// Game files read from the config's `game_dir` (the discovered install when
+/// unset) in its `language`, each part loaded on first use.
+pub struct GameData {
+    content_dir: Option<PathBuf>,
+    language: String,
+    pak: OnceLock<Option<Vpk>>,
+    items: OnceLock<Option<ItemDatabase>>,
+    localization: OnceLock<Option<Localization>>,
+}
+
+static GAME_DATA: RwLock<Option<Arc<GameData>>> = RwLock::new(None);
+
+/// Points [`current`] at `content_dir` and `language`, for the config the
+/// tool runs with. Game data loaded for other settings is dropped once its
+/// last user is done with it.
+pub fn configure(content_dir: Option<PathBuf>, language: &str) {
+    let mut current = GAME_DATA.write().unwrap();
+    match &*current {
+        Some(data) if data.is(&content_dir, language) => return,
+        Some(_) => log::info!("game_dir or language changed, reloading game data"),
+        None => {}
+    }
+    *current = Some(Arc::new(GameData::new(content_dir, language)));
+}
+
+/// The configured game data, defaults until [`configure`] is called.
+pub fn current() -> Arc<GameData> {
+    if let Some(data) = &*GAME_DATA.read().unwrap() {
+        return data.clone();
+    }
+    GAME_DATA
+        .write()
+        .unwrap()
+        .get_or_insert_with(|| Arc::new(GameData::new(None, DEFAULT_LANGUAGE)))
+        .clone()
+}
+
+/// Game data for `content_dir` and `language`: the current one when it has
+/// those settings, else one of its own that is not shared.
+pub fn with_settings(content_dir: &Option<PathBuf>, language: &str) -> Arc<GameData> {
+    if let Some(current) = &*GAME_DATA.read().unwrap()
+        && current.is(content_dir, language)
+    {
+        return current.clone();
+    }
+    Arc::new(GameData::new(content_dir.clone(), language))
+}
+
+impl GameData {
+    pub fn new(content_dir: Option<PathBuf>, language: &str) -> Self {
+        Self {
+            content_dir,
+            language: language.to_string(),
+            pak: OnceLock::new(),
+            items: OnceLock::new(),
+            localization: OnceLock::new(),
+        }
+    }
+
+    fn is(&self, content_dir: &Option<PathBuf>, language: &str) -> bool {
+        self.content_dir == *content_dir && self.language == language
+    }
+
+    /// The config's `game_dir`.
+    pub fn configured_content_dir(&self) -> Option<&PathBuf> {
+        self.content_dir.as_ref()
+    }
+
+    /// `game/csgo` directory of the install: the config's `game_dir`, or the
+    /// discovered one.
+    pub fn content_dir(&self) -> Option<PathBuf> {
+        self.content_dir
+            .clone()
+            .or_else(|| install().map(|install| install.content_dir.clone()))
+    }
+
+    /// The game's `pak01_dir.vpk`, opened on first use.
+    pub fn pak(&self) -> Option<&Vpk> {
+        self.pak.get_or_init(|| self.open_pak()).as_ref()
+    }
+
+    fn open_pak(&self) -> Option<Vpk> {
+        let path = self.content_dir()?.join(PAK_NAME);
+        match Vpk::open(&path) {
+            Ok(vpk) => Some(vpk),
+            Err(e) => {
+                log::warn!("could not open {path:?}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Reads a game file from an extracted copy in `BASE_PATH`, a loose file
+    /// in the game directory, or the game's VPK. Returns the bytes and where
+    /// they came from.
+    pub fn read_file(&self, path: &str) -> Option<(Vec<u8>, String)> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let loose = self.content_dir().map(|dir| dir.join(path));
+        for file in std::iter::once(BASE_PATH.join(name)).chain(loose) {
+            if let Ok(data) = std::fs::read(&file) {
+                return Some((data, format!("{file:?}")));
+            }
+        }
+        let pak = self.pak()?;
+        match pak.read(path) {
+            Ok(data) => Some((data, format!("{path} in {PAK_NAME}"))),
+            Err(e) => {
+                log::warn!("could not read {path} from {PAK_NAME}: {e}");
+                None
+            }
+        }
+    }
+
+    /// The item database, loaded on first use. `None` when `items_game.txt`
+    /// is missing or does not parse.
+    pub fn items(&self) -> Option<&ItemDatabase> {
+        self.items.get_or_init(|| self.load_items()).as_ref()
+    }
+
+    fn load_items(&self) -> Option<ItemDatabase> {
+        let Some((data, source)) = self.read_file(ITEMS_GAME_PATH) else {
+            log::info!("no items_game.txt found, using built-in weapon table");
+            return None;
+        };
+        match ItemDatabase::parse(&String::from_utf8_lossy(&data)) {
+            Ok(items) => {
+                log::info!(
+                    "loaded {} items and {} paint kits from {source}",
+                    items.items().count(),
+                    items.paint_kits().count()
+                );
+                Some(items)
+            }
+            Err(e) => {
+                log::warn!("could not load item database from {source}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Localization in the config's `language`, loaded on first use.
+    pub fn localization(&self) -> Option<&Localization> {
+        self.localization
+            .get_or_init(|| self.load_localizations())
+            .as_ref()
+    }
+
+    fn load_localization(&self, language: &str) -> Option<Localization> {
+        let (data, source) = self.read_file(&format!("resource/csgo_{language}.txt"))?;
+        match Localization::parse(&data) {
+            Ok(localization) => {
+                log::info!(
+                    "loaded {} {language} tokens from {source}",
+                    localization.len()
+                );
+                Some(localization)
+            }
+            Err(e) => {
+                log::warn!("could not load localization from {source}: {e}");
+                None
+            }
+        }
+    }
+
+    fn load_localizations(&self) -> Option<Localization> {
+        let language = &self.language;
+        let localization = self.load_localization(language);
+        if language == DEFAULT_LANGUAGE {
+            return localization;
+        }
+        // untranslated tokens fall back to English
+        let fallback = self.load_localization(DEFAULT_LANGUAGE);
+        match (localization, fallback) {
+            (Some(mut localization), Some(fallback)) => {
+                localization.fill_from(fallback);
+                Some(localization)
+            }
+            (localization, fallback) => {
+                log::warn!("no {language} localization, using {DEFAULT_LANGUAGE}");
+                localization.or(fallback)
+            }
+        }
+    }
+
+    /// Localized text of a `#Token`.
+    pub fn localize(&self, token: &str) -> Option<&str> {
+        self.localization()?.get(token)
+    }
+
+    pub fn weapon_name(&self, weapon: &Weapon) -> Option<&str> {
+        let item = self.items()?.item_by_name(weapon.item_name()?)?;
+        self.localize(item.item_name.as_deref()?)
+    }
+
+    pub fn paint_kit_name(&self, id: u32) -> Option<&str> {
+        self.localize(self.items()?.paint_kit(id)?.description_tag.as_deref()?)
+    }
+
+    pub fn sticker_kit_name(&self, id: u32) -> Option<&str> {
+        self.localize(self.items()?.sticker_kit(id)?.item_name.as_deref()?)
+    }
+
+    /// Localized name of a quality, `StatTrak™` for [`STATTRAK_QUALITY`].
+    pub fn quality_name(&self, value: u32) -> Option<&str> {
+        self.localize(self.items()?.quality(value)?)
+    }
+
+    /// Name as shown in game, `StatTrak™ AK-47 | Asiimov`. Paint kits without
+    /// a localized name show their ID.
+    pub fn skin_name(&self, weapon: &Weapon, paint_kit: i32, stattrak: bool) -> String {
+        let mut name = self
+            .weapon_name(weapon)
+            .unwrap_or(weapon.english_name())
+            .to_string();
+        if stattrak {
+            let quality = self.quality_name(STATTRAK_QUALITY).unwrap_or("StatTrak");
+            name = format!("{quality} {name}");
+        }
+        if paint_kit > 0 {
+            match self.paint_kit_name(paint_kit as u32) {
+                Some(kit) => name = format!("{name} | {kit}"),
+                None => name = format!("{name} | paint kit {paint_kit}"),
+            }
+        }
+        name
+    }
+}
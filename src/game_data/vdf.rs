@@ -0,0 +1,264 @@
+use std::fmt::Display;
+
+/// A KeyValues (VDF) node: a string, or an ordered list of pairs that may
+/// repeat keys, as `items_game.txt` does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vdf {
+    String(String),
+    Object(Vec<(String, Vdf)>),
+}
+
+#[derive(Debug)]
+pub struct VdfError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for VdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    String(String),
+    /// `[$WIN32]`-style platform conditional, ignored
+    Conditional,
+    Open,
+    Close,
+}
+
+struct Lexer<'a> {
+    input: &'a [u8],
+    position: usize,
+    line: usize,
+}
+
+impl Lexer<'_> {
+    fn error(&self, message: impl Into<String>) -> VdfError {
+        VdfError {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&c) = self.input.get(self.position) {
+            if c == b'\n' {
+                self.line += 1;
+                self.position += 1;
+            } else if c.is_ascii_whitespace() {
+                self.position += 1;
+            } else if self.input[self.position..].starts_with(b"//") {
+                while self.input.get(self.position).is_some_and(|&c| c != b'\n') {
+                    self.position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, VdfError> {
+        self.skip_whitespace_and_comments();
+        let Some(&c) = self.input.get(self.position) else {
+            return Ok(None);
+        };
+        match c {
+            b'{' => {
+                self.position += 1;
+                Ok(Some(Token::Open))
+            }
+            b'}' => {
+                self.position += 1;
+                Ok(Some(Token::Close))
+            }
+            b'"' => self.quoted().map(|s| Some(Token::String(s))),
+            b'[' => {
+                while self.input.get(self.position).is_some_and(|&c| c != b']') {
+                    self.position += 1;
+                }
+                if self.position >= self.input.len() {
+                    return Err(self.error("unclosed conditional"));
+                }
+                self.position += 1;
+                Ok(Some(Token::Conditional))
+            }
+            _ => {
+                let start = self.position;
+                while self
+                    .input
+                    .get(self.position)
+                    .is_some_and(|&c| !c.is_ascii_whitespace() && !b"{}\"".contains(&c))
+                {
+                    self.position += 1;
+                }
+                let token = String::from_utf8_lossy(&self.input[start..self.position]);
+                Ok(Some(Token::String(token.into_owned())))
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, VdfError> {
+        let start_line = self.line;
+        self.position += 1;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&c) = self.input.get(self.position) else {
+                return Err(VdfError {
+                    line: start_line,
+                    message: "unclosed string".to_string(),
+                });
+            };
+            self.position += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = self.input.get(self.position).copied();
+                    self.position += 1;
+                    match escaped {
+                        Some(b'n') => bytes.push(b'\n'),
+                        Some(b't') => bytes.push(b'\t'),
+                        Some(b'\\') => bytes.push(b'\\'),
+                        Some(b'"') => bytes.push(b'"'),
+                        // unknown escapes are kept as written, Windows paths use them
+                        Some(other) => bytes.extend([b'\\', other]),
+                        None => {}
+                    }
+                }
+                b'\n' => {
+                    self.line += 1;
+                    bytes.push(c);
+                }
+                _ => bytes.push(c),
+            }
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+impl Vdf {
+    /// Parses a text KeyValues file. The root is an object of every top level pair.
+    pub fn parse(input: &str) -> Result<Self, VdfError> {
+        // UTF-8 BOM, common in localization files
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut lexer = Lexer {
+            input: input.as_bytes(),
+            position: 0,
+            line: 1,
+        };
+        let pairs = Self::parse_object(&mut lexer, true)?;
+        Ok(Vdf::Object(pairs))
+    }
+
+    fn parse_object(lexer: &mut Lexer<'_>, root: bool) -> Result<Vec<(String, Vdf)>, VdfError> {
+        let mut pairs = Vec::new();
+        loop {
+            let key = match lexer.next()? {
+                Some(Token::String(key)) => key,
+                Some(Token::Close) if !root => return Ok(pairs),
+                Some(Token::Conditional) => continue,
+                None if root => return Ok(pairs),
+                None => return Err(lexer.error("unexpected end of file, missing }")),
+                Some(token) => return Err(lexer.error(format!("expected a key, found {token:?}"))),
+            };
+            let value = loop {
+                match lexer.next()? {
+                    Some(Token::String(value)) => break Vdf::String(value),
+                    Some(Token::Open) => break Vdf::Object(Self::parse_object(lexer, false)?),
+                    Some(Token::Conditional) => continue,
+                    _ => return Err(lexer.error(format!("missing value for {key:?}"))),
+                }
+            };
+            pairs.push((key, value));
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Vdf::String(value) => Some(value),
+            Vdf::Object(_) => None,
+        }
+    }
+
+    /// Pairs of an object, empty for strings.
+    pub fn entries(&self) -> &[(String, Vdf)] {
+        match self {
+            Vdf::String(_) => &[],
+            Vdf::Object(pairs) => pairs,
+        }
+    }
+
+    /// Every value stored under `key`, keys are case-insensitive.
+    pub fn get_all<'a>(&'a self, key: &str) -> impl Iterator<Item = &'a Vdf> {
+        self.entries()
+            .iter()
+            .filter(move |(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    /// First value stored under `key`.
+    pub fn get(&self, key: &str) -> Option<&Vdf> {
+        self.get_all(key).next()
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(Vdf::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_escapes_and_comments() {
+        let root = Vdf::parse(
+            "\u{feff}\"root\" // comment\n{\n\t\"quote\" \"a \\\"q\\\" \\\\ b\\n\\tc\"\n\t\"path\" \"C:\\Program Files\"\n\tbare value\n}",
+        )
+        .unwrap();
+        let root = root.get("ROOT").unwrap();
+        assert_eq!(root.get_str("quote"), Some("a \"q\" \\ b\n\tc"));
+        // unknown escapes stay as written
+        assert_eq!(root.get_str("path"), Some("C:\\Program Files"));
+        assert_eq!(root.get_str("bare"), Some("value"));
+    }
+
+    #[test]
+    fn skips_conditionals() {
+        let root = Vdf::parse(
+            "\"items\" { \"525\" [$WIN32] { \"name\" \"a\" } \"526\" { \"name\" \"b\" [!$X360] } }",
+        )
+        .unwrap();
+        let items = root.get("items").unwrap();
+        assert_eq!(items.get("525").unwrap().get_str("name"), Some("a"));
+        assert_eq!(items.get("526").unwrap().get_str("name"), Some("b"));
+    }
+
+    #[test]
+    fn keeps_duplicate_keys() {
+        let root = Vdf::parse("\"a\" \"1\" \"A\" { \"b\" \"2\" } \"a\" \"3\"").unwrap();
+        assert_eq!(root.entries().len(), 3);
+        assert_eq!(root.get_str("a"), Some("1"));
+        let all: Vec<&Vdf> = root.get_all("a").collect();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[1].get_str("b"), Some("2"));
+        assert_eq!(all[2].as_str(), Some("3"));
+    }
+
+    #[test]
+    fn reports_errors_with_lines() {
+        let error = |input| Vdf::parse(input).unwrap_err();
+        let unclosed = error("\"a\"\n{\n\"b\" \"c\"\n");
+        assert_eq!(unclosed.line, 4);
+        assert!(unclosed.message.contains("missing }"));
+        assert_eq!(error("\"a\" {\n\"b\" \"c\n}").line, 2);
+        assert!(error("\"a\" }").message.contains("missing value"));
+        assert!(
+            error("\"a\" [$WIN32")
+                .message
+                .contains("unclosed conditional")
+        );
+    }
+}
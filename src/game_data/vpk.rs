@@ -0,0 +1,504 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+const SIGNATURE: u32 = 0x55AA_1234;
+const VERSION: u32 = 2;
+const HEADER_SIZE: u64 = 28;
+/// Archive index of files stored in the directory file after the tree.
+const DIR_ARCHIVE: u16 = 0x7FFF;
+const ENTRY_TERMINATOR: u16 = 0xFFFF;
+
+#[derive(Debug)]
+pub enum VpkError {
+    Io(io::Error),
+    Format(String),
+    NotFound(String),
+    Crc {
+        path: String,
+        expected: u32,
+        actual: u32,
+    },
+}
+
+impl Display for VpkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VpkError::Io(e) => write!(f, "{e}"),
+            VpkError::Format(e) => write!(f, "not a VPK v2 directory file: {e}"),
+            VpkError::NotFound(path) => write!(f, "{path} is not in the archive"),
+            VpkError::Crc {
+                path,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{path}: CRC 0x{actual:08X} does not match 0x{expected:08X}"
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for VpkError {
+    fn from(e: io::Error) -> Self {
+        VpkError::Io(e)
+    }
+}
+
+/// A file in the directory tree.
+#[derive(Debug, Clone)]
+pub struct VpkEntry {
+    /// Lowercase path with `/` separators, `scripts/items/items_game.txt`
+    pub path: String,
+    pub crc: u32,
+    /// Leading bytes stored in the tree itself.
+    pub preload: Vec<u8>,
+    pub archive_index: u16,
+    pub offset: u32,
+    pub length: u32,
+}
+
+impl VpkEntry {
+    pub fn size(&self) -> u64 {
+        self.preload.len() as u64 + self.length as u64
+    }
+}
+
+/// A VPK v2 directory file (`pak01_dir.vpk`) and its numbered archives.
+#[derive(Debug)]
+pub struct Vpk {
+    dir_path: PathBuf,
+    /// Start of the data stored in the directory file.
+    data_offset: u64,
+    entries: BTreeMap<String, VpkEntry>,
+}
+
+impl Vpk {
+    pub fn open(path: &Path) -> Result<Self, VpkError> {
+        let mut file = File::open(path)?;
+        let mut header = [0u8; HEADER_SIZE as usize];
+        file.read_exact(&mut header)?;
+        let field =
+            |index: usize| u32::from_le_bytes(header[index * 4..index * 4 + 4].try_into().unwrap());
+        if field(0) != SIGNATURE {
+            return Err(VpkError::Format("bad signature".to_string()));
+        }
+        if field(1) != VERSION {
+            return Err(VpkError::Format(format!("version {}", field(1))));
+        }
+        let tree_size = field(2) as usize;
+        if tree_size as u64 > file.metadata()?.len().saturating_sub(HEADER_SIZE) {
+            return Err(VpkError::Format(format!(
+                "tree of {tree_size} bytes is larger than the file"
+            )));
+        }
+
+        let mut tree = vec![0u8; tree_size];
+        file.read_exact(&mut tree)?;
+        let entries = parse_tree(&tree)?;
+        Ok(Self {
+            dir_path: path.to_path_buf(),
+            data_offset: HEADER_SIZE + tree_size as u64,
+            entries,
+        })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &VpkEntry> {
+        self.entries.values()
+    }
+
+    /// Looks up a file, case-insensitive and with either separator.
+    pub fn entry(&self, path: &str) -> Option<&VpkEntry> {
+        self.entries.get(&normalize(path))
+    }
+
+    /// Reads a whole file and checks its CRC.
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, VpkError> {
+        let entry = self
+            .entry(path)
+            .ok_or_else(|| VpkError::NotFound(path.to_string()))?;
+        let mut data = entry.preload.clone();
+        if entry.length > 0 {
+            let (archive, offset) = if entry.archive_index == DIR_ARCHIVE {
+                (
+                    self.dir_path.clone(),
+                    self.data_offset + entry.offset as u64,
+                )
+            } else {
+                (self.archive_path(entry.archive_index), entry.offset as u64)
+            };
+            let mut file = File::open(&archive)?;
+            file.seek(SeekFrom::Start(offset))?;
+            // grows with what the file holds rather than the length the tree claims
+            let read = file.take(entry.length as u64).read_to_end(&mut data)?;
+            if read < entry.length as usize {
+                return Err(VpkError::Io(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("{archive:?} ends inside {}", entry.path),
+                )));
+            }
+        }
+
+        let actual = crc32(&data);
+        if actual != entry.crc {
+            return Err(VpkError::Crc {
+                path: entry.path.clone(),
+                expected: entry.crc,
+                actual,
+            });
+        }
+        Ok(data)
+    }
+
+    /// `pak01_dir.vpk` -> `pak01_003.vpk`
+    fn archive_path(&self, index: u16) -> PathBuf {
+        let name = self
+            .dir_path
+            .file_name()
+            .map(|name| {
+                name.to_string_lossy()
+                    .replace("_dir.vpk", &format!("_{index:03}.vpk"))
+            })
+            .unwrap_or_default();
+        self.dir_path.with_file_name(name)
+    }
+}
+
+fn normalize(path: &str) -> String {
+    path.trim_start_matches(['/', '\\'])
+        .replace('\\', "/")
+        .to_ascii_lowercase()
+}
+
+/// The tree is grouped by extension, then directory, then file name, each
+/// level ended by an empty string. A single space stands for "none".
+fn parse_tree(tree: &[u8]) -> Result<BTreeMap<String, VpkEntry>, VpkError> {
+    let mut reader = TreeReader { tree, position: 0 };
+    let mut entries = BTreeMap::new();
+    loop {
+        let extension = reader.string()?;
+        if extension.is_empty() {
+            break;
+        }
+        loop {
+            let directory = reader.string()?;
+            if directory.is_empty() {
+                break;
+            }
+            loop {
+                let name = reader.string()?;
+                if name.is_empty() {
+                    break;
+                }
+                let mut path = String::new();
+                if directory != " " {
+                    path.push_str(&directory);
+                    path.push('/');
+                }
+                path.push_str(&name);
+                if extension != " " {
+                    path.push('.');
+                    path.push_str(&extension);
+                }
+                let path = normalize(&path);
+
+                let crc = reader.u32()?;
+                let preload_size = reader.u16()?;
+                let archive_index = reader.u16()?;
+                let offset = reader.u32()?;
+                let length = reader.u32()?;
+                if reader.u16()? != ENTRY_TERMINATOR {
+                    return Err(VpkError::Format(format!("bad entry terminator for {path}")));
+                }
+                let preload = reader.bytes(preload_size as usize)?.to_vec();
+                entries.insert(
+                    path.clone(),
+                    VpkEntry {
+                        path,
+                        crc,
+                        preload,
+                        archive_index,
+                        offset,
+                        length,
+                    },
+                );
+            }
+        }
+    }
+    Ok(entries)
+}
+
+struct TreeReader<'a> {
+    tree: &'a [u8],
+    position: usize,
+}
+
+impl<'a> TreeReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], VpkError> {
+        let bytes = self
+            .tree
+            .get(self.position..self.position + len)
+            .ok_or_else(|| VpkError::Format("tree ends early".to_string()))?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn string(&mut self) -> Result<String, VpkError> {
+        let rest = &self.tree[self.position.min(self.tree.len())..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| VpkError::Format("unterminated string in tree".to_string()))?;
+        self.position += len + 1;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+
+    fn u16(&mut self) -> Result<u16, VpkError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, VpkError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+}
+
+/// CRC-32 (IEEE, reflected 0xEDB88320), the checksum VPK entries store.
+pub fn crc32(data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One file of a test archive. Its data is appended to the directory
+    /// file or to the numbered archive it names.
+    struct TestFile {
+        extension: &'static str,
+        directory: &'static str,
+        name: &'static str,
+        preload: &'static [u8],
+        archive: u16,
+        data: &'static [u8],
+        crc: Option<u32>,
+    }
+
+    /// Writes `pak01_dir.vpk` and its numbered archives into a fresh
+    /// directory and returns the directory file's path.
+    fn write_vpk(test: &str, files: &[TestFile]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vpk-test-{}-{test}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut tree = Vec::new();
+        let mut dir_data = Vec::new();
+        let mut archives: BTreeMap<u16, Vec<u8>> = BTreeMap::new();
+        let string = |tree: &mut Vec<u8>, s: &str| {
+            tree.extend_from_slice(s.as_bytes());
+            tree.push(0);
+        };
+        // one extension and directory level per file keeps the builder simple
+        for file in files {
+            string(&mut tree, file.extension);
+            string(&mut tree, file.directory);
+            string(&mut tree, file.name);
+            let data = if file.archive == DIR_ARCHIVE {
+                &mut dir_data
+            } else {
+                archives.entry(file.archive).or_default()
+            };
+            let offset = data.len() as u32;
+            data.extend_from_slice(file.data);
+            let whole = [file.preload, file.data].concat();
+            tree.extend_from_slice(&file.crc.unwrap_or_else(|| crc32(&whole)).to_le_bytes());
+            tree.extend_from_slice(&(file.preload.len() as u16).to_le_bytes());
+            tree.extend_from_slice(&file.archive.to_le_bytes());
+            tree.extend_from_slice(&offset.to_le_bytes());
+            tree.extend_from_slice(&(file.data.len() as u32).to_le_bytes());
+            tree.extend_from_slice(&ENTRY_TERMINATOR.to_le_bytes());
+            tree.extend_from_slice(file.preload);
+            string(&mut tree, "");
+            string(&mut tree, "");
+        }
+        string(&mut tree, "");
+
+        let mut out = Vec::new();
+        for field in [SIGNATURE, VERSION, tree.len() as u32, 0, 0, 0, 0] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        out.extend_from_slice(&tree);
+        out.extend_from_slice(&dir_data);
+        let dir_path = dir.join("pak01_dir.vpk");
+        std::fs::write(&dir_path, out).unwrap();
+        for (index, data) in archives {
+            std::fs::write(dir.join(format!("pak01_{index:03}.vpk")), data).unwrap();
+        }
+        dir_path
+    }
+
+    fn file(name: &'static str, archive: u16, data: &'static [u8]) -> TestFile {
+        TestFile {
+            extension: "txt",
+            directory: "scripts/items",
+            name,
+            preload: b"",
+            archive,
+            data,
+            crc: None,
+        }
+    }
+
+    #[test]
+    fn looks_up_paths_in_tree() {
+        let path = write_vpk(
+            "lookup",
+            &[
+                file("items_game", 0, b"items"),
+                TestFile {
+                    extension: " ",
+                    directory: " ",
+                    name: "README",
+                    ..file("", 0, b"root")
+                },
+            ],
+        );
+        let vpk = Vpk::open(&path).unwrap();
+        assert_eq!(vpk.entries().count(), 2);
+        assert!(vpk.entry("scripts/items/items_game.txt").is_some());
+        assert!(vpk.entry("\\Scripts\\Items\\ITEMS_GAME.txt").is_some());
+        assert_eq!(vpk.entry("readme").unwrap().size(), 4);
+        assert!(matches!(
+            vpk.read("scripts/items/missing.txt"),
+            Err(VpkError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn reads_preload_and_dir_archive() {
+        let path = write_vpk(
+            "dir",
+            &[
+                TestFile {
+                    preload: b"only preload",
+                    ..file("preload", 0, b"")
+                },
+                TestFile {
+                    preload: b"head ",
+                    ..file("split", DIR_ARCHIVE, b"tail")
+                },
+                file("dir", DIR_ARCHIVE, b"after the tree"),
+            ],
+        );
+        let vpk = Vpk::open(&path).unwrap();
+        assert_eq!(
+            vpk.read("scripts/items/preload.txt").unwrap(),
+            b"only preload"
+        );
+        assert_eq!(vpk.read("scripts/items/split.txt").unwrap(), b"head tail");
+        assert_eq!(
+            vpk.read("scripts/items/dir.txt").unwrap(),
+            b"after the tree"
+        );
+    }
+
+    #[test]
+    fn reads_numbered_archives() {
+        let path = write_vpk(
+            "archives",
+            &[
+                file("first", 0, b"in 000"),
+                file("second", 1, b"in 001"),
+                file("third", 1, b"also in 001"),
+            ],
+        );
+        let vpk = Vpk::open(&path).unwrap();
+        let third = vpk.entry("scripts/items/third.txt").unwrap();
+        assert_eq!((third.archive_index, third.offset), (1, 6));
+        assert_eq!(vpk.read("scripts/items/first.txt").unwrap(), b"in 000");
+        assert_eq!(vpk.read("scripts/items/second.txt").unwrap(), b"in 001");
+        assert_eq!(vpk.read("scripts/items/third.txt").unwrap(), b"also in 001");
+    }
+
+    #[test]
+    fn reports_crc_mismatch() {
+        let path = write_vpk(
+            "crc",
+            &[TestFile {
+                crc: Some(0x1234_5678),
+                ..file("corrupt", 0, b"data")
+            }],
+        );
+        let vpk = Vpk::open(&path).unwrap();
+        match vpk.read("scripts/items/corrupt.txt") {
+            Err(VpkError::Crc {
+                expected, actual, ..
+            }) => {
+                assert_eq!(expected, 0x1234_5678);
+                assert_eq!(actual, crc32(b"data"));
+            }
+            other => panic!("expected a CRC error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        let path = write_vpk("format", &[]);
+        let mut data = std::fs::read(&path).unwrap();
+        data[4] = 1;
+        std::fs::write(&path, data).unwrap();
+        assert!(matches!(Vpk::open(&path), Err(VpkError::Format(_))));
+    }
+
+    #[test]
+    fn rejects_sizes_past_the_end_of_the_file() {
+        let path = write_vpk("sizes", &[file("short", DIR_ARCHIVE, b"data")]);
+        let data = std::fs::read(&path).unwrap();
+
+        // a tree larger than the file is not allocated
+        let mut oversized = data.clone();
+        oversized[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, oversized).unwrap();
+        assert!(matches!(Vpk::open(&path), Err(VpkError::Format(_))));
+
+        // data cut short
+        std::fs::write(&path, &data[..data.len() - 2]).unwrap();
+        let vpk = Vpk::open(&path).unwrap();
+        assert!(matches!(
+            vpk.read("scripts/items/short.txt"),
+            Err(VpkError::Io(e)) if e.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn crc32_matches_ieee() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
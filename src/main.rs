@@ -1,14 +1,17 @@
 use std::io::Write;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+mod commands;
 mod config;
 mod constants;
 mod cs2;
+mod game_data;
 mod os;
 
-use config::{parse_config, write_config, CONFIG_PATH, DEFAULT_CONFIG_NAME};
-use cs2::CS2;
+use commands::console::ConsoleCommand;
+use config::{load_config, migrate_file, write_config, Condition, Presets, SkinChangerConfig, CONFIG_PATH, DEFAULT_CONFIG_NAME, PRESETS_NAME};
+use cs2::{CS2, tick::TickScheduler};
 
 #[cfg(not(target_os = "linux"))]
 compile_error!("only linux is supported.");
@@ -31,23 +34,69 @@ fn main() {
         return;
     }
 
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let profile = match take_option(&mut args, "--profile") {
+        Ok(profile) => profile,
+        Err(e) => {
+            log::error!("{e}");
+            std::process::exit(1);
+        }
+    };
+    if let Some(command) = args.first() {
+        let success = commands::run(command, &args[1..]);
+        std::process::exit(if success { 0 } else { 1 });
+    }
+
     // Load config
     let config_path = CONFIG_PATH.join(DEFAULT_CONFIG_NAME);
-    let mut config = parse_config(&config_path);
+    if !config_path.exists() {
+        // Enable skin changer by default for demonstration
+        let config = SkinChangerConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        match write_config(&config, &config_path) {
+            Ok(()) => log::info!("Wrote default config. Edit the config file to add specific skins."),
+            Err(e) => log::warn!("could not write default config: {e}"),
+        }
+    }
+    // upgraded once here, reloads only validate the file
+    if let Err(e) = migrate_file(&config_path) {
+        log::error!("could not migrate config: {e}, not starting.");
+        std::process::exit(1);
+    }
+    Presets::write_default(&CONFIG_PATH.join(PRESETS_NAME));
+    let (mut file_config, mut config) = match load_config(&config_path, profile.as_deref()) {
+        Ok(configs) => configs,
+        Err(e) => {
+            log::error!("{e}, not starting. Run validate-config to check the file.");
+            std::process::exit(1);
+        }
+    };
+    game_data::configure(config.game_dir.clone(), &config.language);
 
+    if let Some(profile) = &config.active_profile {
+        log::info!("Profile: {profile}");
+    }
     log::info!("Skin changer enabled: {}", config.enabled);
     log::info!("Config path: {:?}", config_path);
 
     // Print configured skins
     let mut configured_skins = 0;
     for (weapon, skin_config) in &config.skins {
-        if skin_config.enabled && skin_config.paint_kit > 0 {
-            log::info!(
-                "  {:?}: PaintKit={}, Seed={}, Wear={:.4}, StatTrak={}",
+        if skin_config.enabled && skin_config.paint_kit.id() > 0 {
+            let name = game_data::current().skin_name(
                 weapon,
+                skin_config.paint_kit.id(),
+                skin_config.stattrak >= 0,
+            );
+            let wear = skin_config.wear.value();
+            log::info!(
+                "  {name}: PaintKit={}, Seed={}, Wear={:.4} ({}), StatTrak={}",
                 skin_config.paint_kit,
                 skin_config.seed,
-                skin_config.wear,
+                wear,
+                Condition::from_float(wear),
                 skin_config.stattrak
             );
             configured_skins += 1;
@@ -57,24 +106,56 @@ fn main() {
     if configured_skins == 0 {
         log::warn!("No skins configured! Edit the config file to add skins.");
         log::info!("Example: Set enabled=true and paint_kit to a valid skin ID for any weapon.");
-        
-        // Enable skin changer by default for demonstration
-        config.enabled = true;
-        write_config(&config, &config_path);
-        log::info!("Enabled skin changer in config. Edit the config file to add specific skins.");
     }
 
     // Main loop
     let mut cs2 = CS2::new();
-    let loop_duration = Duration::from_millis(10); // 100Hz update rate
+    let mut scheduler = TickScheduler::new();
+    let console = commands::console::spawn();
+    let config_changes = os::watch::watch_file(&config_path);
+    // profile picked with --profile or on the console, kept across reloads
+    let mut selected_profile = profile;
 
     log::info!("Starting main loop...");
     log::info!("Press Ctrl+C to exit");
 
     loop {
+        for command in console.try_iter() {
+            match command {
+                ConsoleCommand::Profile(name) => match file_config.resolve_profile(Some(&name)) {
+                    Ok(profile_config) => {
+                        log::info!("switched to profile {name}");
+                        cs2.reapply(config.changed_weapons(&profile_config));
+                        config = profile_config;
+                        selected_profile = Some(name);
+                    }
+                    Err(e) => log::error!("{e}, keeping current profile"),
+                },
+                ConsoleCommand::Profiles => {
+                    commands::profiles::print(&file_config, config.active_profile.as_deref())
+                }
+            }
+        }
+
+        if let Some(changes) = &config_changes
+            && changes.try_iter().count() > 0
+        {
+            match load_config(&config_path, selected_profile.as_deref()) {
+                Ok((new_file_config, new_config)) => {
+                    let changed = config.changed_weapons(&new_config);
+                    log::info!("config reloaded, {} weapons changed", changed.len());
+                    game_data::configure(new_config.game_dir.clone(), &new_config.language);
+                    cs2.reapply(changed);
+                    file_config = new_file_config;
+                    config = new_config;
+                }
+                Err(e) => log::error!("config reload rejected, keeping the previous config: {e}"),
+            }
+        }
+
         if !cs2.is_valid() {
             log::info!("Waiting for CS2 process...");
-            cs2.setup();
+            cs2.setup(&config);
             if !cs2.is_valid() {
                 thread::sleep(Duration::from_secs(5));
                 continue;
@@ -82,7 +163,54 @@ fn main() {
             log::info!("CS2 found!");
         }
 
-        cs2.run(&config);
-        thread::sleep(loop_duration);
+        // wakes on tick changes, backs off while the game is idle
+        let wake = scheduler.update(cs2.tick_state(), Instant::now());
+        if wake.run {
+            cs2.run(&config);
+        }
+        thread::sleep(wake.sleep);
+    }
+}
+
+/// Removes `name <value>` from anywhere in `args` and returns the value.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>, String> {
+    let Some(at) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if at + 1 >= args.len() {
+        return Err(format!("{name} needs a value"));
+    }
+    let value = args.remove(at + 1);
+    args.remove(at);
+    Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn takes_profile_anywhere() {
+        for (given, rest) in [
+            (args(&["--profile", "faceit"]), args(&[])),
+            (args(&["--profile", "faceit", "profiles"]), args(&["profiles"])),
+            (args(&["profiles", "--profile", "faceit"]), args(&["profiles"])),
+            (
+                args(&["schema-diff", "--profile", "faceit", "old.json"]),
+                args(&["schema-diff", "old.json"]),
+            ),
+        ] {
+            let mut left = given.clone();
+            assert_eq!(take_option(&mut left, "--profile"), Ok(Some("faceit".to_string())));
+            assert_eq!(left, rest, "{given:?}");
+        }
+
+        let mut none = args(&["profiles"]);
+        assert_eq!(take_option(&mut none, "--profile"), Ok(None));
+        assert!(take_option(&mut args(&["profiles", "--profile"]), "--profile").is_err());
     }
 }
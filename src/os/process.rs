@@ -12,6 +12,23 @@ use nix::libc::{self, iovec, process_vm_readv};
 
 use crate::constants::{cs2, elf};
 
+/// One line of `/proc/<pid>/maps`.
+#[derive(Debug, Clone)]
+pub struct Mapping {
+    pub start: u64,
+    pub end: u64,
+    pub perms: String,
+    /// Backing file, `[heap]`-style pseudo path, or empty for anonymous memory.
+    pub path: String,
+}
+
+impl Mapping {
+    /// Heap or anonymous read-write memory.
+    pub fn is_heap(&self) -> bool {
+        (self.path.is_empty() || self.path == "[heap]") && self.perms.starts_with("rw")
+    }
+}
+
 #[derive(Debug)]
 pub struct Process {
     pub pid: i32,
@@ -96,7 +113,6 @@ impl Process {
         t
     }
 
-    #[allow(dead_code)]
     pub fn read_vec(&self, address: u64, length: usize) -> Vec<u8> {
         let mut buffer = vec![0u8; length];
 
@@ -135,6 +151,23 @@ impl Process {
         unsafe { nix::libc::process_vm_writev(self.pid, &local_iov, 1, &remote_iov, 1, 0) };
     }
 
+    #[cfg(feature = "read-only")]
+    pub fn write_bytes(&self, _address: u64, _bytes: &[u8]) {}
+
+    #[cfg(not(feature = "read-only"))]
+    pub fn write_bytes(&self, address: u64, bytes: &[u8]) {
+        let local_iov = iovec {
+            iov_base: bytes.as_ptr() as *mut libc::c_void,
+            iov_len: bytes.len(),
+        };
+        let remote_iov = iovec {
+            iov_base: address as *mut libc::c_void,
+            iov_len: bytes.len(),
+        };
+
+        unsafe { nix::libc::process_vm_writev(self.pid, &local_iov, 1, &remote_iov, 1, 0) };
+    }
+
     #[allow(dead_code)]
     pub fn read_string(&self, address: u64) -> String {
         if let Some(cached) = STRING_CACHE.with(|c| c.borrow().get(&address).cloned()) {
@@ -190,6 +223,35 @@ impl Process {
         None
     }
 
+    pub fn mappings(&self) -> Vec<Mapping> {
+        let Ok(maps) = File::open(format!("/proc/{}/maps", self.pid)) else {
+            return Vec::new();
+        };
+        BufReader::new(maps)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let (start, end) = parts.next()?.split_once('-')?;
+                let perms = parts.next()?.to_string();
+                let path = parts.nth(3).unwrap_or_default().to_string();
+                Some(Mapping {
+                    start: u64::from_str_radix(start, 16).ok()?,
+                    end: u64::from_str_radix(end, 16).ok()?,
+                    perms,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the mapping that contains `address`.
+    pub fn mapping_of(&self, address: u64) -> Option<Mapping> {
+        self.mappings()
+            .into_iter()
+            .find(|m| (m.start..m.end).contains(&address))
+    }
+
     pub fn dump_module(&self, address: u64) -> Vec<u8> {
         let module_size = self.module_size(address);
         self.read_bytes(address, module_size)
@@ -251,26 +313,29 @@ impl Process {
             .wrapping_add(rip_address as u64)
     }
 
-    pub fn get_interface_offset(&self, base_address: u64, interface_name: &str) -> Option<u64> {
-        let create_interface = self.get_module_export(base_address, "CreateInterface")?;
+    /// Walks the `CreateInterface` registration list of a module and returns
+    /// every registered interface name with its instance address.
+    pub fn list_interfaces(&self, base_address: u64) -> Vec<(String, u64)> {
+        let mut interfaces = Vec::new();
+        let Some(create_interface) = self.get_module_export(base_address, "CreateInterface")
+        else {
+            return interfaces;
+        };
         let export_address = create_interface + 0x10;
 
-        let mut interface_entry =
+        let mut interface_entry: u64 =
             self.read(export_address + 0x07 + self.read::<u32>(export_address + 0x03) as u64);
 
-        loop {
+        // guard against walking garbage if the list layout ever changes
+        while interface_entry != 0 && interfaces.len() < 1024 {
             let entry_name_address = self.read(interface_entry + 8);
             let entry_name = self.read_string_uncached(entry_name_address);
-            if entry_name.starts_with(interface_name) {
-                let vfunc_address = self.read::<u64>(interface_entry);
-                return Some(self.read::<u32>(vfunc_address + 0x03) as u64 + vfunc_address + 0x07);
-            }
+            let vfunc_address = self.read::<u64>(interface_entry);
+            let instance = self.read::<u32>(vfunc_address + 0x03) as u64 + vfunc_address + 0x07;
+            interfaces.push((entry_name, instance));
             interface_entry = self.read(interface_entry + 0x10);
-            if interface_entry == 0 {
-                break;
-            }
         }
-        None
+        interfaces
     }
 
     pub fn get_module_export(&self, base_address: u64, export_name: &str) -> Option<u64> {
@@ -289,7 +354,7 @@ impl Process {
             }
             symbol_table += add;
         }
-        log::warn!("export {} could not be found", export_name);
+        log::debug!("export {} could not be found", export_name);
         None
     }
 
@@ -333,6 +398,37 @@ impl Process {
         None
     }
 
+    /// Reads the GNU build ID note of a loaded module as a hex string.
+    pub fn module_build_id(&self, base_address: u64) -> Option<String> {
+        let first_entry =
+            self.read::<u64>(base_address + elf::PROGRAM_HEADER_OFFSET) + base_address;
+        let entry_size = self.read::<u16>(base_address + elf::PROGRAM_HEADER_ENTRY_SIZE) as u64;
+
+        for i in 0..self.read::<u16>(base_address + elf::PROGRAM_HEADER_NUM_ENTRIES) {
+            let entry = first_entry + i as u64 * entry_size;
+            if self.read::<u32>(entry) as u64 != elf::NOTE_SEGMENT_PHT_TYPE {
+                continue;
+            }
+            let mut note = base_address + self.read::<u64>(entry + 0x10);
+            let end = note + self.read::<u64>(entry + 0x28);
+            while note + 12 <= end {
+                let name_size = self.read::<u32>(note) as u64;
+                let desc_size = self.read::<u32>(note + 4) as u64;
+                let note_type = self.read::<u32>(note + 8);
+                let name = note + 12;
+                let desc = name + name_size.next_multiple_of(4);
+                if note_type == elf::NOTE_GNU_BUILD_ID
+                    && self.read_vec(name, name_size as usize) == b"GNU\0"
+                {
+                    let id = self.read_vec(desc, desc_size as usize);
+                    return Some(id.iter().map(|b| format!("{b:02x}")).collect());
+                }
+                note = desc + desc_size.next_multiple_of(4);
+            }
+        }
+        None
+    }
+
     pub fn module_size(&self, address: u64) -> u64 {
         let section_header_offset = self.read::<u64>(address + elf::SECTION_HEADER_OFFSET);
         let section_header_entry_size =
@@ -343,7 +439,7 @@ impl Process {
         section_header_offset + section_header_entry_size * section_header_num_entries
     }
 
-    fn get_pid(process_name: &str) -> Option<i32> {
+    pub(crate) fn get_pid(process_name: &str) -> Option<i32> {
         for dir in read_dir("/proc").unwrap() {
             let entry = dir.unwrap();
             if !entry.file_type().unwrap().is_dir() {
@@ -0,0 +1,44 @@
+use std::{
+    path::Path,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+
+/// Sends a message every time `path` is written or replaced.
+///
+/// Watches the parent directory, since editors often save by writing a new
+/// file and renaming it over the old one, which a watch on the file itself
+/// would not survive.
+pub fn watch_file(path: &Path) -> Option<Receiver<()>> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_os_string();
+
+    let inotify = Inotify::init(InitFlags::IN_CLOEXEC)
+        .map_err(|e| log::warn!("could not init inotify: {e}"))
+        .ok()?;
+    inotify
+        .add_watch(dir, AddWatchFlags::IN_CLOSE_WRITE | AddWatchFlags::IN_MOVED_TO)
+        .map_err(|e| log::warn!("could not watch {dir:?}: {e}"))
+        .ok()?;
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        loop {
+            let events = match inotify.read_events() {
+                Ok(events) => events,
+                Err(e) => {
+                    log::warn!("inotify read failed, stopping config watch: {e}");
+                    break;
+                }
+            };
+            if events.iter().any(|event| event.name.as_ref() == Some(&name))
+                && sender.send(()).is_err()
+            {
+                break;
+            }
+        }
+    });
+    Some(receiver)
+}